@@ -0,0 +1,145 @@
+//! Compare checking every obstacle against looking them up in the spatial hash, both for contacts
+//! and for spacing obstacles out when generating a layout.
+//!
+//! Run with `cargo bench --no-default-features`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use r_circlegauntlet::collision::circle_contact;
+use r_circlegauntlet::glm::{distance2, Vec2};
+use r_circlegauntlet::spatial::{SpatialHash, DEFAULT_CELL_SIZE};
+use r_circlegauntlet::{GameConfig, Level, PLAYER_RADIUS};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+const QUERIES: usize = 100;
+/// Random spots to try for each obstacle, the same as `Level::scatter` does
+const PLACEMENT_TRIES: usize = 10_000;
+
+fn circles(count: usize, radius: f32) -> Vec<(Vec2, f32)> {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    (0..count)
+        .map(|_| {
+            let pos = Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+            (pos, radius)
+        })
+        .collect()
+}
+
+fn queries() -> Vec<Vec2> {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    (0..QUERIES)
+        .map(|_| Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)))
+        .collect()
+}
+
+fn naive(obstacles: &[(Vec2, f32)], queries: &[Vec2]) -> usize {
+    queries
+        .iter()
+        .map(|&pos| {
+            obstacles
+                .iter()
+                .filter(|&&(other, radius)| {
+                    circle_contact(pos, PLAYER_RADIUS, other, radius).is_some()
+                })
+                .count()
+        })
+        .sum()
+}
+
+fn hashed(grid: &SpatialHash, obstacles: &[(Vec2, f32)], queries: &[Vec2]) -> usize {
+    queries
+        .iter()
+        .map(|&pos| {
+            grid.query(pos, PLAYER_RADIUS)
+                .into_iter()
+                .filter(|&i| {
+                    let (other, radius) = obstacles[i];
+                    circle_contact(pos, PLAYER_RADIUS, other, radius).is_some()
+                })
+                .count()
+        })
+        .sum()
+}
+
+fn contacts(c: &mut Criterion) {
+    let queries = queries();
+    let mut group = c.benchmark_group("player contacts");
+    for &count in &[16, 256, 4096] {
+        // Shrink obstacles as there are more of them so the arena isn't solid
+        let obstacles = circles(count, 0.5 / (count as f32).sqrt());
+        let grid = SpatialHash::from_circles(DEFAULT_CELL_SIZE, &obstacles);
+        assert_eq!(
+            naive(&obstacles, &queries),
+            hashed(&grid, &obstacles, &queries)
+        );
+        group.bench_with_input(
+            BenchmarkId::new("naive", count),
+            &obstacles,
+            |b, obstacles| b.iter(|| naive(black_box(obstacles), black_box(&queries))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("hashed", count),
+            &obstacles,
+            |b, obstacles| {
+                b.iter(|| hashed(black_box(&grid), black_box(obstacles), black_box(&queries)))
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Place obstacles just like `Level::scatter` does, but checking each spot against every obstacle
+/// placed so far
+fn naive_placement(config: &GameConfig) -> Vec<Vec2> {
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+    let keep_clear = [Vec2::new(-0.75, 0.75), Vec2::new(0.75, -0.75)];
+    let mut placed: Vec<Vec2> = vec![];
+    for _ in 0..config.obstacle_count {
+        let spot = (0..PLACEMENT_TRIES)
+            .map(|_| Vec2::new(rng.gen::<f32>() * 2.0 - 1.0, rng.gen::<f32>() * 2.0 - 1.0))
+            .find(|pos| {
+                keep_clear
+                    .iter()
+                    .chain(&placed)
+                    .all(|other| distance2(pos, other) >= config.obstacle_spacing)
+            });
+        placed.extend(spot);
+    }
+    placed
+}
+
+fn hashed_placement(config: &GameConfig) -> Level {
+    Level::scatter(config, &mut ChaCha8Rng::seed_from_u64(config.seed))
+}
+
+fn placement(c: &mut Criterion) {
+    let mut group = c.benchmark_group("placement");
+    for &count in &[16, 256, 2048] {
+        let config = GameConfig {
+            obstacle_count: count,
+            // Tight enough that thousands of obstacles still fit
+            obstacle_spacing: 0.0005,
+            enemy_spacing: 0.0005,
+            // Only obstacles, so both sides do the same work
+            enemy_count: 0,
+            ..Default::default()
+        };
+        let placed: Vec<Vec2> = hashed_placement(&config)
+            .obstacles
+            .iter()
+            .map(|obstacle| Vec2::new(obstacle.pos[0], obstacle.pos[1]))
+            .collect();
+        assert_eq!(naive_placement(&config), placed);
+        group.bench_with_input(BenchmarkId::new("naive", count), &config, |b, config| {
+            b.iter(|| naive_placement(black_box(config)))
+        });
+        // Just the placement, not the solver checking each layout
+        group.bench_with_input(BenchmarkId::new("hashed", count), &config, |b, config| {
+            b.iter(|| hashed_placement(black_box(config)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, contacts, placement);
+criterion_main!(benches);
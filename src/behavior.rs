@@ -0,0 +1,150 @@
+//! How enemies decide where to go.
+//!
+//! Every enemy has a `Behavior` component holding an `EnemyBehavior`.  Each step the behavior is
+//! shown what the enemy can see and picks a point to head for; the game then accelerates the enemy
+//! toward that point the same way no matter which behavior picked it.
+
+use crate::level::{EnemyKind, EnemySpec};
+use rusty_core::glm::{self, Vec2};
+
+/// How close the player has to get before an ambusher springs, if the level doesn't say
+pub const DEFAULT_AMBUSH_RANGE: f32 = 0.5;
+/// How far from the goal a goal guard circles, if the level doesn't say
+pub const DEFAULT_ORBIT: f32 = 0.3;
+/// How close a patroller has to get to a waypoint before moving on to the next one
+const WAYPOINT_REACHED: f32 = 0.05;
+/// Never lead the player by more than this many seconds.  Further out than that, guessing where
+/// they'll be is just guessing.
+const MAX_LEAD_TIME: f32 = 2.;
+
+/// Everything an enemy knows about the world when deciding where to go
+#[derive(Clone, Copy, Debug)]
+pub struct Surroundings<'a> {
+    pub pos: Vec2,
+    pub vel: Vec2,
+    pub player_pos: Vec2,
+    pub player_vel: Vec2,
+    pub goals: &'a [Vec2],
+    /// The fastest this enemy can go
+    pub max_speed: f32,
+}
+
+/// A way of picking where an enemy goes
+pub trait EnemyBehavior: Send + Sync {
+    /// Where to head this step, or `None` to coast
+    fn target(&mut self, surroundings: &Surroundings) -> Option<Vec2>;
+}
+
+/// Build the behavior an enemy from a level file asks for
+pub fn from_spec(spec: &EnemySpec) -> Box<dyn EnemyBehavior> {
+    match spec.kind {
+        EnemyKind::Chaser => Box::new(Chaser),
+        EnemyKind::Interceptor => Box::new(Interceptor),
+        EnemyKind::Patroller => Box::new(Patroller::new(
+            spec.waypoints.iter().copied().map(Vec2::from).collect(),
+        )),
+        EnemyKind::Ambusher => Box::new(Ambusher::new(spec.range.unwrap_or(DEFAULT_AMBUSH_RANGE))),
+        EnemyKind::GoalGuard => Box::new(GoalGuard::new(spec.orbit.unwrap_or(DEFAULT_ORBIT))),
+    }
+}
+
+/// Heads straight for wherever the player is right now
+pub struct Chaser;
+
+impl EnemyBehavior for Chaser {
+    fn target(&mut self, surroundings: &Surroundings) -> Option<Vec2> {
+        Some(surroundings.player_pos)
+    }
+}
+
+/// Heads for where the player will be by the time it gets there, if they keep going the way
+/// they're going
+pub struct Interceptor;
+
+impl EnemyBehavior for Interceptor {
+    fn target(&mut self, surroundings: &Surroundings) -> Option<Vec2> {
+        let distance = glm::distance(&surroundings.pos, &surroundings.player_pos);
+        let lead = if surroundings.max_speed > 0. {
+            (distance / surroundings.max_speed).min(MAX_LEAD_TIME)
+        } else {
+            0.
+        };
+        Some(surroundings.player_pos + surroundings.player_vel * lead)
+    }
+}
+
+/// Walks a loop of waypoints, ignoring the player entirely
+pub struct Patroller {
+    waypoints: Vec<Vec2>,
+    next: usize,
+}
+
+impl Patroller {
+    pub fn new(waypoints: Vec<Vec2>) -> Self {
+        Self { waypoints, next: 0 }
+    }
+}
+
+impl EnemyBehavior for Patroller {
+    fn target(&mut self, surroundings: &Surroundings) -> Option<Vec2> {
+        let waypoint = *self.waypoints.get(self.next)?;
+        if glm::distance(&surroundings.pos, &waypoint) < WAYPOINT_REACHED {
+            self.next = (self.next + 1) % self.waypoints.len();
+        }
+        Some(self.waypoints[self.next])
+    }
+}
+
+/// Sits still until the player comes within `range`, then chases them for good
+pub struct Ambusher {
+    range: f32,
+    sprung: bool,
+}
+
+impl Ambusher {
+    pub fn new(range: f32) -> Self {
+        Self {
+            range,
+            sprung: false,
+        }
+    }
+}
+
+impl EnemyBehavior for Ambusher {
+    fn target(&mut self, surroundings: &Surroundings) -> Option<Vec2> {
+        if glm::distance(&surroundings.pos, &surroundings.player_pos) < self.range {
+            self.sprung = true;
+        }
+        if self.sprung {
+            Some(surroundings.player_pos)
+        } else {
+            None
+        }
+    }
+}
+
+/// Circles the nearest goal at a distance of `orbit`, counter-clockwise
+pub struct GoalGuard {
+    orbit: f32,
+}
+
+impl GoalGuard {
+    pub fn new(orbit: f32) -> Self {
+        Self { orbit }
+    }
+}
+
+impl EnemyBehavior for GoalGuard {
+    fn target(&mut self, surroundings: &Surroundings) -> Option<Vec2> {
+        let pos = surroundings.pos;
+        let goal = surroundings
+            .goals
+            .iter()
+            .copied()
+            .min_by(|a, b| glm::distance2(&pos, a).total_cmp(&glm::distance2(&pos, b)))?;
+        // Aim for a point a little further around the circle than where we are now
+        let offset = pos - goal;
+        let angle = offset[1].atan2(offset[0]) + std::f32::consts::FRAC_PI_3;
+        Some(goal + Vec2::new(angle.cos(), angle.sin()) * self.orbit)
+    }
+}
@@ -0,0 +1,171 @@
+//! Campaigns: an ordered list of levels played one after another.
+//!
+//! A campaign file lists level files relative to itself:
+//!
+//! ```toml
+//! name = "Circle Gauntlet"
+//! life = "carry"
+//! levels = ["01_first_steps.toml", "02_the_wall.toml"]
+//! ```
+
+use crate::game::GameConfig;
+use crate::level::Level;
+use crate::session::Session;
+use crate::storage::{self, FileError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// What happens to the player's life between levels
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifeRule {
+    /// Whatever life is left at the end of a level is what you start the next one with
+    #[default]
+    Carry,
+    /// Every level starts with full life
+    Reset,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Campaign {
+    pub name: String,
+    #[serde(default)]
+    pub life: LifeRule,
+    /// Level files, in the order they are played
+    pub levels: Vec<PathBuf>,
+}
+
+impl Campaign {
+    /// Load a campaign file.  Level paths in it are resolved relative to the campaign file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FileError> {
+        let path = path.as_ref();
+        let mut campaign = storage::load_file(path, Self::parse)?;
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        for level in campaign.levels.iter_mut() {
+            *level = dir.join(&level);
+        }
+        Ok(campaign)
+    }
+
+    /// Parse the contents of a campaign file, leaving the level paths as they are
+    pub fn parse(text: &str) -> Result<Self, FileError> {
+        let campaign: Campaign = toml::from_str(text).map_err(|err| FileError::Parse {
+            path: PathBuf::new(),
+            kind: "campaign",
+            message: err.to_string().trim_end().to_string(),
+        })?;
+        if campaign.levels.is_empty() {
+            return Err(FileError::Invalid {
+                path: PathBuf::new(),
+                field: "levels".into(),
+                message: "a campaign needs at least one level".into(),
+            });
+        }
+        Ok(campaign)
+    }
+}
+
+/// What comes after winning a level
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Advance {
+    /// Move on to the level with this index
+    NextLevel(usize),
+    /// That was the last level
+    Finished,
+}
+
+/// A playthrough of a campaign: which level we're on and how much life we're carrying
+pub struct CampaignRun {
+    campaign: Campaign,
+    config: GameConfig,
+    level: usize,
+    life: i32,
+}
+
+impl CampaignRun {
+    /// Start playing `campaign` at level index `start` (clamped to the last level)
+    pub fn new(campaign: Campaign, config: GameConfig, start: usize) -> Self {
+        let level = start.min(campaign.levels.len() - 1);
+        Self {
+            life: config.life_max,
+            campaign,
+            config,
+            level,
+        }
+    }
+
+    pub fn campaign(&self) -> &Campaign {
+        &self.campaign
+    }
+
+    /// Index of the level currently being played
+    pub fn level_index(&self) -> usize {
+        self.level
+    }
+
+    /// Play the rest of the campaign with `config`, e.g. after a different difficulty was picked on
+    /// the title screen
+    pub fn set_config(&mut self, config: GameConfig) {
+        self.config = config;
+    }
+
+    /// Life the current level starts with
+    pub fn life(&self) -> i32 {
+        self.life
+    }
+
+    /// Load the current level and set up a session to play it
+    pub fn start_level(&self) -> Result<Session, FileError> {
+        let level = Level::load(&self.campaign.levels[self.level])?;
+        Ok(Session::new(self.config.clone(), Some(level)).with_life(self.life))
+    }
+
+    /// The current level was won with `life` left over
+    pub fn level_won(&mut self, life: i32) -> Advance {
+        if self.level + 1 >= self.campaign.levels.len() {
+            return Advance::Finished;
+        }
+        self.level += 1;
+        self.life = match self.campaign.life {
+            LifeRule::Carry => life,
+            LifeRule::Reset => self.config.life_max,
+        };
+        Advance::NextLevel(self.level)
+    }
+}
+
+/// How far the player has gotten in each campaign, saved between runs
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Progress {
+    /// Campaign name -> index of the furthest level unlocked
+    pub unlocked: BTreeMap<String, usize>,
+}
+
+impl Progress {
+    /// Where progress is saved by default
+    pub fn default_path() -> Option<PathBuf> {
+        storage::data_dir().map(|dir| dir.join("progress.toml"))
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        storage::load_toml(path)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        storage::save_toml(path, self)
+    }
+
+    /// Index of the furthest level unlocked in `campaign`
+    pub fn unlocked(&self, campaign: &Campaign) -> usize {
+        self.unlocked.get(&campaign.name).copied().unwrap_or(0)
+    }
+
+    /// Record that `level` has been unlocked in `campaign`.  Never goes backwards.
+    pub fn unlock(&mut self, campaign: &Campaign, level: usize) {
+        let unlocked = self.unlocked.entry(campaign.name.clone()).or_insert(0);
+        *unlocked = (*unlocked).max(level);
+    }
+}
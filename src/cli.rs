@@ -0,0 +1,199 @@
+//! Command-line option parsing for the game binary
+
+use crate::difficulty::Difficulty;
+use crate::game::GameConfig;
+use crate::level::Boundary;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: r_circlegauntlet [OPTIONS]
+       r_circlegauntlet validate-level <path>
+       r_circlegauntlet scores [--export <path>]
+
+Commands:
+    validate-level <path>  Check that a level file can be beaten and report its narrowest passage
+    scores                 List the high scores on every layout won so far.  With --export, write
+                           them all to a CSV file instead (- for the terminal).
+
+Options:
+    --seed <u64>    Generate the layout from this seed instead of a random one
+    --level <path>  Play a level file (see the levels/ directory) instead of a generated layout
+    --difficulty <easy|normal|hard|nightmare|custom>
+                    Start at this difficulty (default normal).  +/- on the title screen changes it.
+    --enemies <n>   How many enemies a generated layout starts with
+    --obstacles <n> How many obstacles a generated layout has
+    --lives <n>     How many lives the player starts with
+    --boundary <deadly|bouncy|wrap|painful>
+                    What the edges of a generated layout do
+    --campaign      Play the bundled levels in order, resuming at the last level unlocked
+    --record <path> Save a replay of the last attempt to this file when the game closes.  In the
+                    campaign, each level gets its own file: run.json becomes run-1.json, etc.
+    --replay <path> Watch a replay saved with --record
+    --physics <path>
+                    Tune movement from this file instead of physics.toml.  Edits to it apply
+                    while playing.
+    -h, --help      Print this message
+
+--enemies, --obstacles, --lives and --boundary override the difficulty preset, which makes the
+difficulty custom.";
+
+/// What the binary has been asked to do
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Command {
+    /// Open the window and play
+    #[default]
+    Play,
+    /// Check a level file without playing it
+    ValidateLevel(PathBuf),
+    /// List the high scores, or export them as CSV to `export`
+    Scores { export: Option<PathBuf> },
+}
+
+/// Everything that can be chosen from the command line
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    pub seed: Option<u64>,
+    pub level: Option<PathBuf>,
+    pub difficulty: Option<Difficulty>,
+    pub enemies: Option<usize>,
+    pub obstacles: Option<usize>,
+    pub lives: Option<i32>,
+    pub boundary: Option<Boundary>,
+    pub campaign: bool,
+    pub physics: Option<PathBuf>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Options {
+    /// Parse options from `args`, which should *not* include the program name
+    pub fn parse<I, S>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut options = Options::default();
+        let mut export = None;
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "validate-level" => {
+                    let value = args.next().ok_or("validate-level needs a path")?;
+                    options.command = Command::ValidateLevel(value.into());
+                }
+                "scores" => options.command = Command::Scores { export: None },
+                "--export" => {
+                    let value = args.next().ok_or("--export needs a path")?;
+                    export = Some(value.into());
+                }
+                "--seed" => {
+                    let value = args.next().ok_or("--seed needs a value")?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("invalid seed '{}': expected a u64", value))?;
+                    options.seed = Some(seed);
+                }
+                "--level" => {
+                    let value = args.next().ok_or("--level needs a path")?;
+                    options.level = Some(value.into());
+                }
+                "--enemies" => {
+                    let value = args.next().ok_or("--enemies needs a count")?;
+                    let enemies = value.parse().map_err(|_| {
+                        format!("invalid enemy count '{}': expected a whole number", value)
+                    })?;
+                    options.enemies = Some(enemies);
+                }
+                "--difficulty" => {
+                    let value = args.next().ok_or("--difficulty needs a name")?;
+                    let difficulty = Difficulty::from_name(&value).ok_or_else(|| {
+                        format!(
+                            "invalid difficulty '{}': expected easy, normal, hard, nightmare or \
+                             custom",
+                            value
+                        )
+                    })?;
+                    options.difficulty = Some(difficulty);
+                }
+                "--obstacles" => {
+                    let value = args.next().ok_or("--obstacles needs a count")?;
+                    let obstacles = value.parse().map_err(|_| {
+                        format!(
+                            "invalid obstacle count '{}': expected a whole number",
+                            value
+                        )
+                    })?;
+                    options.obstacles = Some(obstacles);
+                }
+                "--lives" => {
+                    let value = args.next().ok_or("--lives needs a count")?;
+                    let lives = value
+                        .parse()
+                        .ok()
+                        .filter(|&lives: &i32| lives > 0)
+                        .ok_or_else(|| {
+                            format!("invalid life count '{}': expected at least 1", value)
+                        })?;
+                    options.lives = Some(lives);
+                }
+                "--boundary" => {
+                    let value = args.next().ok_or("--boundary needs a kind")?;
+                    let boundary = match value.to_ascii_lowercase().as_str() {
+                        "deadly" => Boundary::Deadly,
+                        "bouncy" => Boundary::Bouncy,
+                        "wrap" => Boundary::Wrap,
+                        "painful" => Boundary::Painful,
+                        _ => {
+                            return Err(format!(
+                                "invalid boundary '{}': expected deadly, bouncy, wrap or painful",
+                                value
+                            ))
+                        }
+                    };
+                    options.boundary = Some(boundary);
+                }
+                "--campaign" => options.campaign = true,
+                "--physics" => {
+                    let value = args.next().ok_or("--physics needs a path")?;
+                    options.physics = Some(value.into());
+                }
+                "--record" => {
+                    let value = args.next().ok_or("--record needs a path")?;
+                    options.record = Some(value.into());
+                }
+                "--replay" => {
+                    let value = args.next().ok_or("--replay needs a path")?;
+                    options.replay = Some(value.into());
+                }
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("unrecognized argument '{}'", arg)),
+            }
+        }
+        if let Some(path) = export {
+            match &mut options.command {
+                Command::Scores { export } => *export = Some(path),
+                _ => return Err("--export only goes with the scores command".into()),
+            }
+        }
+        Ok(options)
+    }
+
+    /// `base` set up for the chosen difficulty, with any individual overrides on top
+    pub fn game_config(&self, base: GameConfig) -> GameConfig {
+        let mut config = self.difficulty.unwrap_or_default().apply(base);
+        let overridden = self.enemies.is_some()
+            || self.obstacles.is_some()
+            || self.lives.is_some()
+            || self.boundary.is_some();
+        if overridden {
+            config.difficulty = Difficulty::Custom;
+        }
+        config.enemy_count = self.enemies.unwrap_or(config.enemy_count);
+        config.obstacle_count = self.obstacles.unwrap_or(config.obstacle_count);
+        config.life_max = self.lives.unwrap_or(config.life_max);
+        config.boundary = self.boundary.unwrap_or(config.boundary);
+        config
+    }
+}
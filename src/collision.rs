@@ -0,0 +1,382 @@
+//! Contact detection and resolution for circles running into circles and rectangles.
+//!
+//! Moving circles are swept along their path rather than only checked where they end up, so a big
+//! step can't carry something straight through a thin obstacle.  Rectangles are handled in their
+//! own frame, where they're axis-aligned and centered on the origin: a circle touches one exactly
+//! when its center touches the rectangle grown by the circle's radius, with rounded corners.
+
+use rusty_core::glm::{self, Vec2};
+
+/// How many times to sweep over all contacts when resolving.  Pushing out of one circle can push
+/// into another when they're clustered together, so a few passes are needed to settle.
+const RESOLVE_ITERATIONS: usize = 4;
+
+/// How many impacts to follow within a single `sweep_circle` before giving up and letting
+/// `resolve_circle` sort out whatever is left
+const SWEEP_IMPACTS: usize = 4;
+
+/// How two overlapping shapes touch
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+    /// Unit vector pointing out of the other shape toward the circle being resolved
+    pub normal: Vec2,
+    /// How far they overlap along `normal`
+    pub depth: f32,
+}
+
+/// The contact between a circle at `pos` and one at `other_pos`, if they overlap
+pub fn circle_contact(
+    pos: Vec2,
+    radius: f32,
+    other_pos: Vec2,
+    other_radius: f32,
+) -> Option<Contact> {
+    let offset = pos - other_pos;
+    let distance = offset.magnitude();
+    let depth = radius + other_radius - distance;
+    if depth <= 0. {
+        return None;
+    }
+    // Perfectly on top of each other there's no right answer, so just pick a direction
+    let normal = if distance > f32::EPSILON {
+        offset / distance
+    } else {
+        Vec2::new(0., 1.)
+    };
+    Some(Contact { normal, depth })
+}
+
+/// Bounce `vel` off of a surface facing `normal`.  `restitution` is how much of the speed into the
+/// surface comes back out: 1.0 is a perfectly elastic bounce, 0.0 slides along the surface.
+/// Velocity already heading away from the surface is left alone.
+pub fn bounce(vel: Vec2, normal: Vec2, restitution: f32) -> Vec2 {
+    let into = vel.dot(&normal);
+    if into >= 0. {
+        return vel;
+    }
+    vel - normal * (1. + restitution) * into
+}
+
+/// The outline of something that can be run into, centered on its position
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shape {
+    Circle {
+        radius: f32,
+    },
+    /// A rectangle reaching `half_size` from its center to its sides, turned `angle` radians
+    /// counter-clockwise
+    Rect {
+        half_size: Vec2,
+        angle: f32,
+    },
+}
+
+impl Shape {
+    /// Radius of the smallest circle around the shape
+    pub fn bounding_radius(&self) -> f32 {
+        match *self {
+            Shape::Circle { radius } => radius,
+            Shape::Rect { half_size, .. } => half_size.magnitude(),
+        }
+    }
+
+    /// How far `point` is from the surface of the shape at `center`.  Negative inside it.
+    pub fn distance(&self, center: Vec2, point: Vec2) -> f32 {
+        match *self {
+            Shape::Circle { radius } => (point - center).magnitude() - radius,
+            Shape::Rect { half_size, angle } => {
+                let local = rotate(point - center, -angle);
+                let outside = glm::abs(&local) - half_size;
+                if outside[0] > 0. || outside[1] > 0. {
+                    glm::max(&outside, 0.).magnitude()
+                } else {
+                    outside[0].max(outside[1])
+                }
+            }
+        }
+    }
+
+    /// Unit vector pointing out of the shape at `center`, from the part of its surface nearest
+    /// `point`
+    pub fn normal(&self, center: Vec2, point: Vec2) -> Vec2 {
+        match *self {
+            Shape::Circle { .. } => (point - center)
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(|| Vec2::new(0., 1.)),
+            Shape::Rect { half_size, angle } => {
+                let local = rotate(point - center, -angle);
+                let nearest = glm::clamp_vec(&local, &-half_size, &half_size);
+                let normal = match (local - nearest).try_normalize(f32::EPSILON) {
+                    Some(normal) => normal,
+                    // Inside (or right on the surface): out through the closest side
+                    None if half_size[0] - local[0].abs() < half_size[1] - local[1].abs() => {
+                        Vec2::new(local[0].signum(), 0.)
+                    }
+                    None => Vec2::new(0., local[1].signum()),
+                };
+                rotate(normal, angle)
+            }
+        }
+    }
+
+    /// The contact between a circle at `pos` and the shape at `center`, if they overlap
+    pub fn contact(&self, center: Vec2, pos: Vec2, radius: f32) -> Option<Contact> {
+        match *self {
+            Shape::Circle {
+                radius: other_radius,
+            } => circle_contact(pos, radius, center, other_radius),
+            Shape::Rect { .. } => {
+                let depth = radius - self.distance(center, pos);
+                if depth <= 0. {
+                    return None;
+                }
+                Some(Contact {
+                    normal: self.normal(center, pos),
+                    depth,
+                })
+            }
+        }
+    }
+
+    /// How far along `motion` (0.0 at the start, 1.0 at the end) a circle starting at `pos` first
+    /// touches the shape at `center`.  Like `time_of_impact`, overlapping at the start touches at
+    /// 0.0, and `None` means they never touch.
+    pub fn time_of_impact(
+        &self,
+        center: Vec2,
+        pos: Vec2,
+        motion: Vec2,
+        radius: f32,
+    ) -> Option<f32> {
+        let (half_size, angle) = match *self {
+            Shape::Circle {
+                radius: other_radius,
+            } => return time_of_impact(pos, motion, radius + other_radius, center),
+            Shape::Rect { half_size, angle } => (half_size, angle),
+        };
+        if self.distance(center, pos) <= radius {
+            return Some(0.);
+        }
+        let pos = rotate(pos - center, -angle);
+        let motion = rotate(motion, -angle);
+        // The grown rectangle's flat sides...
+        let mut first: Option<f32> = None;
+        for axis in 0..2 {
+            let other = 1 - axis;
+            for &side in &[-1., 1.] {
+                let plane = side * (half_size[axis] + radius);
+                // Only from outside, heading in
+                if motion[axis] * side >= 0. || pos[axis] * side < plane * side {
+                    continue;
+                }
+                let t = (plane - pos[axis]) / motion[axis];
+                let hit = pos[other] + motion[other] * t;
+                if t <= 1. && hit.abs() <= half_size[other] {
+                    first = Some(first.map_or(t, |first| first.min(t)));
+                }
+            }
+        }
+        // ...and its rounded corners
+        for &corner in &[
+            Vec2::new(-half_size[0], -half_size[1]),
+            Vec2::new(half_size[0], -half_size[1]),
+            Vec2::new(-half_size[0], half_size[1]),
+            Vec2::new(half_size[0], half_size[1]),
+        ] {
+            if let Some(t) = time_of_impact(pos, motion, radius, corner) {
+                first = Some(first.map_or(t, |first| first.min(t)));
+            }
+        }
+        first.map(|t| t.max(0.))
+    }
+}
+
+/// `v` turned `angle` radians counter-clockwise
+fn rotate(v: Vec2, angle: f32) -> Vec2 {
+    let (sin, cos) = angle.sin_cos();
+    Vec2::new(v[0] * cos - v[1] * sin, v[0] * sin + v[1] * cos)
+}
+
+/// Something that can be run into, which may be moving
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Body {
+    /// Where it is at the start of the step
+    pub pos: Vec2,
+    pub vel: Vec2,
+    pub shape: Shape,
+}
+
+impl Body {
+    /// A circle that stays put
+    pub fn fixed(pos: Vec2, radius: f32) -> Self {
+        Self {
+            pos,
+            vel: Vec2::zeros(),
+            shape: Shape::Circle { radius },
+        }
+    }
+
+    /// A rectangle that stays put, reaching `half_size` from `pos` to its sides and turned `angle`
+    /// radians counter-clockwise
+    pub fn wall(pos: Vec2, half_size: Vec2, angle: f32) -> Self {
+        Self {
+            pos,
+            vel: Vec2::zeros(),
+            shape: Shape::Rect { half_size, angle },
+        }
+    }
+
+    /// Where it is `time` seconds into the step
+    pub fn pos_at(&self, time: f32) -> Vec2 {
+        self.pos + self.vel * time
+    }
+}
+
+/// Bounce `vel` off of something moving at `surface_vel`.  The bounce happens in the surface's
+/// frame, so running into something coming the other way sends you back faster.
+pub fn bounce_off(vel: Vec2, surface_vel: Vec2, normal: Vec2, restitution: f32) -> Vec2 {
+    surface_vel + bounce(vel - surface_vel, normal, restitution)
+}
+
+/// Move a circle at `pos` out of every body in `others`, where they are `time` seconds into the
+/// step, and bounce `vel` off of each one it was overlapping.  Returns the indices into `others` of
+/// everything it touched.
+pub fn resolve_circle(
+    pos: &mut Vec2,
+    vel: &mut Vec2,
+    radius: f32,
+    others: &[Body],
+    restitution: f32,
+    time: f32,
+) -> Vec<usize> {
+    let mut touched = vec![];
+    for _ in 0..RESOLVE_ITERATIONS {
+        let mut resolved_any = false;
+        for (i, other) in others.iter().enumerate() {
+            if let Some(contact) = other.shape.contact(other.pos_at(time), *pos, radius) {
+                *pos += contact.normal * contact.depth;
+                *vel = bounce_off(*vel, other.vel, contact.normal, restitution);
+                if !touched.contains(&i) {
+                    touched.push(i);
+                }
+                resolved_any = true;
+            }
+        }
+        if !resolved_any {
+            break;
+        }
+    }
+    touched
+}
+
+/// How far along `motion` (0.0 at the start, 1.0 at the end) a circle starting at `pos` first
+/// touches a circle at `other_pos`, where `radius` is the sum of both radii.  Circles that already
+/// overlap at the start touch at 0.0.  `None` if they never touch during the motion.
+pub fn time_of_impact(pos: Vec2, motion: Vec2, radius: f32, other_pos: Vec2) -> Option<f32> {
+    let offset = pos - other_pos;
+    let c = offset.magnitude_squared() - radius * radius;
+    if c <= 0. {
+        return Some(0.);
+    }
+    let a = motion.magnitude_squared();
+    let b = 2. * offset.dot(&motion);
+    // Not moving, or moving away
+    if a <= f32::EPSILON || b >= 0. {
+        return None;
+    }
+    let discriminant = b * b - 4. * a * c;
+    if discriminant < 0. {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / (2. * a);
+    if t <= 1. {
+        Some(t.max(0.))
+    } else {
+        None
+    }
+}
+
+/// The result of sweeping a circle along its velocity
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Sweep {
+    /// Indices of everything it touched along the way
+    pub touched: Vec<usize>,
+    /// Every point the circle's center travelled through in a straight line: where it started,
+    /// each place it bounced, and where it ended up
+    pub path: Vec<Vec2>,
+}
+
+/// Move a circle at `pos` along `vel` for `dt` seconds, stopping at the first body in `others` it
+/// would hit, bouncing off of it, and carrying on for the rest of the time.  Moving bodies are
+/// swept too, so a circle can be hit by something coming at it as well as run into things.
+/// Anything it was already overlapping, or ends up overlapping, is pushed out with
+/// `resolve_circle`.
+pub fn sweep_circle(
+    pos: &mut Vec2,
+    vel: &mut Vec2,
+    radius: f32,
+    others: &[Body],
+    restitution: f32,
+    dt: f32,
+) -> Sweep {
+    let mut sweep = Sweep {
+        touched: vec![],
+        path: vec![*pos],
+    };
+    let mut elapsed = 0.;
+    for _ in 0..SWEEP_IMPACTS {
+        let remaining = dt - elapsed;
+        let first_impact = others
+            .iter()
+            .enumerate()
+            .filter_map(|(i, other)| {
+                // Work in the other body's frame, where it holds still
+                let motion = (*vel - other.vel) * remaining;
+                let other_pos = other.pos_at(elapsed);
+                other
+                    .shape
+                    .time_of_impact(other_pos, *pos, motion, radius)
+                    .map(|t| (i, t))
+            })
+            // Anything we start out touching is resolve_circle's problem
+            .filter(|&(_, t)| t > 0.)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        match first_impact {
+            Some((i, t)) => {
+                let time = remaining * t;
+                *pos += *vel * time;
+                elapsed += time;
+                sweep.path.push(*pos);
+                let other = &others[i];
+                let normal = other.shape.normal(other.pos_at(elapsed), *pos);
+                *vel = bounce_off(*vel, other.vel, normal, restitution);
+                if !sweep.touched.contains(&i) {
+                    sweep.touched.push(i);
+                }
+            }
+            None => {
+                *pos += *vel * remaining;
+                elapsed = dt;
+                break;
+            }
+        }
+    }
+    // Ran out of impacts to follow; finish the move without looking
+    if elapsed < dt {
+        *pos += *vel * (dt - elapsed);
+    }
+    for i in resolve_circle(pos, vel, radius, others, restitution, dt) {
+        if !sweep.touched.contains(&i) {
+            sweep.touched.push(i);
+        }
+    }
+    sweep.path.push(*pos);
+    sweep
+}
+
+/// Whether a circle moving through the straight-line legs of `path` ever comes within `radius` of
+/// `other_pos`
+pub fn path_touches(path: &[Vec2], radius: f32, other_pos: Vec2) -> bool {
+    path.windows(2)
+        .any(|leg| time_of_impact(leg[0], leg[1] - leg[0], radius, other_pos).is_some())
+}
@@ -0,0 +1,41 @@
+use crate::behavior::EnemyBehavior;
+use rusty_core::glm::Vec2;
+
+pub type Position = Vec2;
+pub struct Velocity(pub Vec2);
+/// Size of a circular entity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Radius(pub f32);
+/// Size and turn of a rectangular entity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rectangle {
+    /// From the center to each side
+    pub half_size: Vec2,
+    /// Radians counter-clockwise
+    pub angle: f32,
+}
+/// Where an entity was at the end of the previous tick, so rendering can interpolate between ticks
+pub struct PrevPosition(pub Vec2);
+/// How an enemy decides where to go
+pub struct Behavior(pub Box<dyn EnemyBehavior>);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Goal;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Obstacle;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Player;
+/// Color (red, green, blue, each 0.0 to 1.0) to draw an entity in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tint(pub [f32; 3]);
+
+impl Tint {
+    pub const GOAL: Tint = Tint([0., 1., 0.]);
+    pub const PLAYER: Tint = Tint([0., 0., 1.]);
+    /// Words written over the arena
+    pub const TEXT: Tint = Tint([1., 1., 1.]);
+    pub const OBSTACLE: Tint = Tint([1., 0., 0.]);
+    pub const ENEMY: Tint = Tint([1., 1., 0.]);
+}
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Enemy;
@@ -0,0 +1,159 @@
+//! Named difficulty presets.  Each one fills in the parts of a `GameConfig` that make the game
+//! easier or harder; `Custom` leaves them however they were set.
+
+use crate::game::GameConfig;
+use crate::level::{Boundary, EnemyKind, Navigation};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+    /// Settings picked one by one rather than from a preset
+    Custom,
+}
+
+impl Difficulty {
+    /// Every difficulty, easiest first
+    pub const ALL: [Difficulty; 5] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Nightmare,
+        Difficulty::Custom,
+    ];
+
+    /// The lowercase name used on the command line and in saved files
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Nightmare => "nightmare",
+            Difficulty::Custom => "custom",
+        }
+    }
+
+    /// The difficulty called `name`, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|difficulty| difficulty.name().eq_ignore_ascii_case(name))
+    }
+
+    /// `config` with this preset's settings, and marked as being played at this difficulty.
+    /// Anything a preset doesn't cover (like the seed) is left alone.  Obstacles, enemies and the
+    /// boundary only matter for generated layouts, since level files bring their own.
+    pub fn apply(self, config: GameConfig) -> GameConfig {
+        let preset = match self.preset() {
+            Some(preset) => preset,
+            None => {
+                return GameConfig {
+                    difficulty: self,
+                    ..config
+                }
+            }
+        };
+        GameConfig {
+            difficulty: self,
+            obstacle_count: preset.obstacle_count,
+            obstacle_spacing: preset.obstacle_spacing,
+            enemy_count: preset.enemy_count,
+            enemy_speed: preset.enemy_speed,
+            enemy_kinds: preset.enemy_kinds.to_vec(),
+            enemy_navigation: preset.enemy_navigation,
+            life_max: preset.life_max,
+            invulnerability: preset.invulnerability,
+            boundary: preset.boundary,
+            ..config
+        }
+    }
+
+    fn preset(self) -> Option<Preset> {
+        let preset = match self {
+            Difficulty::Easy => Preset {
+                obstacle_count: 10,
+                obstacle_spacing: 0.15,
+                enemy_count: 0,
+                enemy_speed: 0.75,
+                enemy_kinds: &[EnemyKind::Chaser],
+                enemy_navigation: Navigation::Bounce,
+                life_max: 15,
+                invulnerability: 1.5,
+                boundary: Boundary::Bouncy,
+            },
+            Difficulty::Normal => Preset {
+                obstacle_count: 16,
+                obstacle_spacing: 0.1,
+                enemy_count: 1,
+                enemy_speed: 1.,
+                enemy_kinds: &[EnemyKind::Chaser],
+                enemy_navigation: Navigation::Bounce,
+                life_max: 10,
+                invulnerability: 1.,
+                boundary: Boundary::Deadly,
+            },
+            Difficulty::Hard => Preset {
+                obstacle_count: 22,
+                obstacle_spacing: 0.08,
+                enemy_count: 2,
+                enemy_speed: 1.25,
+                enemy_kinds: &[EnemyKind::Chaser, EnemyKind::Interceptor],
+                enemy_navigation: Navigation::Pathfind,
+                life_max: 7,
+                invulnerability: 0.75,
+                boundary: Boundary::Deadly,
+            },
+            Difficulty::Nightmare => Preset {
+                obstacle_count: 28,
+                obstacle_spacing: 0.06,
+                enemy_count: 3,
+                enemy_speed: 1.5,
+                enemy_kinds: &[
+                    EnemyKind::Interceptor,
+                    EnemyKind::Ambusher,
+                    EnemyKind::Chaser,
+                ],
+                enemy_navigation: Navigation::Pathfind,
+                life_max: 5,
+                invulnerability: 0.5,
+                boundary: Boundary::Painful,
+            },
+            Difficulty::Custom => return None,
+        };
+        Some(preset)
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Nightmare => "Nightmare",
+            Difficulty::Custom => "Custom",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The settings a preset controls
+struct Preset {
+    obstacle_count: usize,
+    obstacle_spacing: f32,
+    enemy_count: usize,
+    enemy_speed: f32,
+    /// Handed out to enemies in turn, as in `GameConfig::enemy_kinds`
+    enemy_kinds: &'static [EnemyKind],
+    enemy_navigation: Navigation,
+    life_max: i32,
+    invulnerability: f32,
+    boundary: Boundary,
+}
@@ -0,0 +1,760 @@
+use crate::behavior::{self, Surroundings};
+use crate::collision;
+use crate::components::*;
+use crate::difficulty::Difficulty;
+use crate::level::{Boundary, EnemyKind, Level, Navigation};
+use crate::motion::Animation;
+use crate::navigation::NavGrid;
+use crate::physics::PhysicsConfig;
+use crate::spatial::{SpatialHash, DEFAULT_CELL_SIZE};
+use crate::{LIFE_MAX, NEAR_MISS_MARGIN};
+use legion::prelude::*;
+use rusty_core::glm::{distance, Vec2};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Settings used to build a new `Game`
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct GameConfig {
+    /// Which preset these settings came from, or `Custom` if they were picked one by one
+    pub difficulty: Difficulty,
+    /// Seed for everything random about the layout.  The same seed always produces the same level.
+    pub seed: u64,
+    pub life_max: i32,
+    pub obstacle_count: usize,
+    pub obstacle_spacing: f32,
+    /// How many enemies a generated layout starts with
+    pub enemy_count: usize,
+    /// How fast enemies can go, as a multiple of `physics.enemy_max_speed`
+    pub enemy_speed: f32,
+    /// What kind each enemy in a generated layout is.  The first enemy gets the first kind, and so
+    /// on, starting over from the beginning when there are more enemies than kinds.
+    pub enemy_kinds: Vec<EnemyKind>,
+    /// How enemies in a generated layout deal with obstacles
+    pub enemy_navigation: Navigation,
+    /// What happens at the edges of the arena in a generated layout
+    pub boundary: Boundary,
+    /// How far (squared) enemies have to spawn from the player's start and the goal
+    pub enemy_spacing: f32,
+    /// Seconds the player can't be hurt again after losing a life
+    pub invulnerability: f32,
+    /// How the player and enemies move
+    pub physics: PhysicsConfig,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            difficulty: Difficulty::Normal,
+            seed: 0,
+            life_max: LIFE_MAX,
+            obstacle_count: 16,
+            obstacle_spacing: 0.1,
+            enemy_count: 1,
+            enemy_speed: 1.,
+            enemy_kinds: vec![EnemyKind::Chaser],
+            enemy_navigation: Navigation::default(),
+            boundary: Boundary::default(),
+            enemy_spacing: 0.125,
+            invulnerability: 1.0,
+            physics: PhysicsConfig::default(),
+        }
+    }
+}
+
+/// Player input for a single step of the simulation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Input {
+    /// Direction the player wants to accelerate in.  Magnitude should be 0.0 or 1.0.
+    pub direction: Vec2,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self {
+            direction: Vec2::zeros(),
+        }
+    }
+}
+
+/// Things that happened during a step that the outside world (sound, console, etc.) may care about
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameEvent {
+    /// The player hit something and lost a life.  `life` is what's left afterwards.
+    Hit { life: i32 },
+    /// The player reached the goal
+    Won,
+    /// The player ran out of life or left the arena
+    Died,
+}
+
+pub struct Game {
+    // The world was created by this universe, so it has to live as long as the world does
+    _universe: Universe,
+    world: World,
+    config: GameConfig,
+    level: Level,
+    life: i32,
+    /// Seconds left before the player can be hurt again
+    invulnerable: f32,
+    /// Everything that was touching the player at the end of the last step.  Only a new contact
+    /// can hurt, so resting against (or being pinned by) something costs a single life.
+    contacts: HashSet<Entity>,
+    /// Whether the player was touching a painful edge of the arena at the end of the last step.
+    /// Like `contacts`, staying against it only hurts once.
+    touching_edge: bool,
+    /// Everything within `NEAR_MISS_MARGIN` of the player at the end of the last step, and whether
+    /// it has touched the player since it came that close
+    near: HashMap<Entity, bool>,
+    /// How many times something came close to the player and went away again without touching
+    near_misses: u32,
+    /// Every obstacle, in the order they're indexed in `obstacle_grid`
+    obstacles: Vec<Entity>,
+    /// Broadphase for finding the obstacles near the player without checking all of them
+    obstacle_grid: SpatialHash,
+    /// Routes around obstacles for enemies that look for them
+    nav_grid: NavGrid,
+    /// How much room `nav_grid` leaves around obstacles
+    nav_clearance: f32,
+    /// Seconds of simulation so far
+    time: f32,
+    over: bool,
+}
+
+impl Game {
+    /// Start a game on a level randomly generated from `config.seed`
+    pub fn new(config: GameConfig) -> Self {
+        let level = Level::generate(&config);
+        Self::with_level(config, level)
+    }
+
+    /// Start a game on a specific level
+    pub fn with_level(config: GameConfig, level: Level) -> Self {
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+
+        world.insert(
+            (Goal,),
+            level
+                .goals
+                .iter()
+                .map(|goal| (Position::from(goal.pos), Radius(goal.radius), Tint::GOAL))
+                .collect::<Vec<_>>(),
+        );
+        let player_start_pos = Position::from(level.player_start);
+        world.insert(
+            (Player,),
+            vec![(
+                player_start_pos,
+                PrevPosition(player_start_pos),
+                Velocity(Vec2::new(0.0, 0.0)),
+                Radius(level.player_radius),
+                Tint::PLAYER,
+            )],
+        );
+        // One at a time, so the entities line up with the level's obstacles, moving or not
+        let mut obstacles: Vec<Entity> = level
+            .obstacles
+            .iter()
+            .map(|obstacle| {
+                let pos = Position::from(obstacle.pos);
+                let radius = Radius(obstacle.radius);
+                match Animation::from_spec(obstacle) {
+                    Some(animation) => {
+                        let vel = Velocity(animation.initial_velocity());
+                        world.insert(
+                            (Obstacle,),
+                            vec![(
+                                pos,
+                                PrevPosition(pos),
+                                radius,
+                                Tint::OBSTACLE,
+                                vel,
+                                animation,
+                            )],
+                        )[0]
+                    }
+                    None => world.insert(
+                        (Obstacle,),
+                        vec![(pos, PrevPosition(pos), radius, Tint::OBSTACLE)],
+                    )[0],
+                }
+            })
+            .collect();
+        // Walls go on the end, so obstacles and walls share one broadphase
+        obstacles.extend(level.walls.iter().map(|wall| {
+            let (pos, half_size, angle) = wall.rectangle();
+            world.insert(
+                (Obstacle,),
+                vec![(pos, Rectangle { half_size, angle }, Tint::OBSTACLE)],
+            )[0]
+        }));
+        // One grid has to do for every enemy, so leave room for the biggest
+        let nav_clearance = level
+            .enemies
+            .iter()
+            .map(|enemy| enemy.radius)
+            .fold(0., f32::max);
+        let (obstacle_grid, nav_grid) = build_grids(&world, &obstacles, nav_clearance);
+        world.insert(
+            (Enemy,),
+            level
+                .enemies
+                .iter()
+                .map(|enemy| {
+                    let pos = Position::from(enemy.spawn);
+                    (
+                        pos,
+                        PrevPosition(pos),
+                        Velocity(Vec2::new(0.0, 0.0)),
+                        Radius(enemy.radius),
+                        Tint::ENEMY,
+                        Behavior(behavior::from_spec(enemy)),
+                        enemy.navigation,
+                    )
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        Self {
+            _universe: universe,
+            world,
+            life: config.life_max,
+            config,
+            level,
+            invulnerable: 0.,
+            contacts: HashSet::new(),
+            touching_edge: false,
+            near: HashMap::new(),
+            near_misses: 0,
+            obstacles,
+            obstacle_grid,
+            nav_grid,
+            nav_clearance,
+            time: 0.,
+            over: false,
+        }
+    }
+
+    /// Start with `life` instead of a full `config.life_max`, e.g. when carrying life between
+    /// levels
+    pub fn with_life(mut self, life: i32) -> Self {
+        self.life = life;
+        self
+    }
+
+    /// The ECS world holding every entity in the game
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Change how things move from the next step on, e.g. after the physics file was edited
+    pub fn set_physics(&mut self, physics: PhysicsConfig) {
+        self.config.physics = physics;
+    }
+
+    /// The level being played
+    pub fn level(&self) -> &Level {
+        &self.level
+    }
+
+    /// The seed this game's layout was generated from
+    pub fn seed(&self) -> u64 {
+        self.config.seed
+    }
+
+    pub fn life(&self) -> i32 {
+        self.life
+    }
+
+    /// Where the player currently is
+    pub fn player_pos(&self) -> Position {
+        <Read<Position>>::query()
+            .filter(tag_value(&Player))
+            .iter(&self.world)
+            .map(|pos| *pos)
+            .next()
+            .unwrap_or_else(Position::zeros)
+    }
+
+    /// Where every enemy currently is
+    pub fn enemy_positions(&self) -> Vec<Position> {
+        <Read<Position>>::query()
+            .filter(tag_value(&Enemy))
+            .iter(&self.world)
+            .map(|pos| *pos)
+            .collect()
+    }
+
+    /// How big the player is
+    pub fn player_radius(&self) -> f32 {
+        self.level.player_radius
+    }
+
+    /// Seconds of simulation so far
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// How many times an obstacle or enemy came within `NEAR_MISS_MARGIN` of the player and went
+    /// away again without touching
+    pub fn near_misses(&self) -> u32 {
+        self.near_misses
+    }
+
+    /// Seconds left before the player can be hurt again.  Zero when the player is vulnerable.
+    pub fn invulnerable(&self) -> f32 {
+        self.invulnerable
+    }
+
+    /// Whether the player has already won or died.  Once the game is over, `step` does nothing.
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    /// Advance the simulation by `dt` seconds.  The simulation is only deterministic if `dt` is the
+    /// same every step, so drive this with a `FixedTimestep` rather than raw frame times.
+    pub fn step(&mut self, input: &Input, dt: f32) -> Vec<GameEvent> {
+        let mut events = vec![];
+        if self.over {
+            return events;
+        }
+        let world = &mut self.world;
+        let life = &mut self.life;
+        let invulnerable = &mut self.invulnerable;
+        let config = &self.config;
+        let physics = &config.physics;
+        let boundary = self.level.boundary;
+        let mut contacts = HashSet::new();
+        let mut dead = false;
+        let mut won = false;
+        *invulnerable = (*invulnerable - dt).max(0.);
+
+        // Remember where everything was, for render interpolation
+        for (pos, mut prev) in <(Read<Position>, Write<PrevPosition>)>::query().iter_mut(world) {
+            prev.0 = *pos;
+        }
+
+        // Move the obstacles that move.  They're swept from where they were to where they are now,
+        // so anything looking for them has to look that much further.
+        self.time += dt;
+        let mut obstacle_motion: f32 = 0.;
+        let mut animated = false;
+        for (animation, mut pos, mut vel, mut radius) in <(
+            Read<Animation>,
+            Write<Position>,
+            Write<Velocity>,
+            Write<Radius>,
+        )>::query()
+        .iter_mut(world)
+        {
+            animation.update(self.time, dt, &mut pos, &mut vel.0, &mut radius.0);
+            obstacle_motion = obstacle_motion.max(vel.0.magnitude() * dt);
+            animated = true;
+        }
+        if animated {
+            let (obstacle_grid, nav_grid) = build_grids(world, &self.obstacles, self.nav_clearance);
+            self.obstacle_grid = obstacle_grid;
+            self.nav_grid = nav_grid;
+        }
+
+        let goals: Vec<(Position, f32)> = <(Read<Position>, Read<Radius>)>::query()
+            .filter(tag_value(&Goal))
+            .iter(world)
+            .map(|(pos, radius)| (*pos, radius.0))
+            .collect();
+
+        // Adjust player velocity
+        // Save player position for the enemy to see
+        let mut player_pos = Position::new(0.0, 0.0);
+        let mut player_vel = Vec2::zeros();
+        let mut player_radius = 0.;
+        for (pos, mut vel, radius) in <(Read<Position>, Write<Velocity>, Read<Radius>)>::query()
+            .filter(tag_value(&Player))
+            .iter_mut(world)
+        {
+            player_pos = *pos;
+            player_radius = radius.0;
+            // Player's new velocity based on previous velocity and current input
+            let max_vel = physics.max_speed;
+
+            // Apply drag first
+            vel.0 *= 1.0 - physics.drag * dt;
+
+            // Then apply accelleration in the direction of the input
+            let magnitude_before = vel.0.magnitude();
+            vel.0 += input.direction * physics.acceleration * dt;
+
+            // If we're over max velocity, clamp velocity magnitude to the same as before input
+            // accelleration so input only affects direction.
+            if vel.0.magnitude() > max_vel && vel.0.magnitude() > magnitude_before {
+                vel.0 = vel.0.normalize() * magnitude_before;
+            }
+
+            // Almost to the goal?
+            for &(goal_pos, goal_radius) in &goals {
+                let goal_distance = distance(&*pos, &goal_pos);
+                if goal_distance < player_radius + goal_radius {
+                    vel.0 +=
+                        ((goal_pos - *pos).normalize() * dt).normalize() * physics.goal_pull * dt;
+                }
+            }
+            player_vel = vel.0;
+        }
+
+        // Adjust enemy velocity
+        let goal_positions: Vec<Position> = goals.iter().map(|&(pos, _)| pos).collect();
+        let enemies: Vec<Entity> = <Read<Position>>::query()
+            .filter(tag_value(&Enemy))
+            .iter_entities(world)
+            .map(|(entity, _)| entity)
+            .collect();
+        let mut enemy_contacts = vec![];
+        let mut lost_enemies = vec![];
+        for entity in enemies {
+            let mut pos = *world.get_component::<Position>(entity).unwrap();
+            let mut vel = world.get_component::<Velocity>(entity).unwrap().0;
+            let navigation = *world.get_component::<Navigation>(entity).unwrap();
+            let radius = world.get_component::<Radius>(entity).unwrap().0;
+
+            // Enemy's new velocity based on previous velocity and current input
+            let max_vel = physics.enemy_max_speed * config.enemy_speed;
+
+            // Apply drag first
+            vel *= 1.0 - physics.enemy_drag * dt;
+
+            // Then apply acceleration toward wherever the enemy wants to go.  Enemies that find
+            // their way around obstacles turn toward the next step of the route instead, but push
+            // just as hard as if they were heading straight there.
+            let magnitude_before = vel.magnitude();
+            let target = world
+                .get_component_mut::<Behavior>(entity)
+                .unwrap()
+                .0
+                .target(&Surroundings {
+                    pos,
+                    vel,
+                    player_pos,
+                    player_vel,
+                    goals: &goal_positions,
+                    max_speed: max_vel,
+                });
+            if let Some(target) = target {
+                let heading = match navigation {
+                    Navigation::Pathfind => self.nav_grid.steer(pos, target),
+                    Navigation::Ghost | Navigation::Bounce => target,
+                };
+                let pull = (target - pos).magnitude();
+                vel += (heading - pos)
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_else(Vec2::zeros)
+                    * pull
+                    * dt;
+            }
+
+            // If we're over max velocity, clamp velocity magnitude to the same as before input
+            // acceleration so input only affects direction.
+            if vel.magnitude() > max_vel && vel.magnitude() > magnitude_before {
+                vel = vel.normalize() * magnitude_before;
+            }
+
+            // Kill player?  Sweep the enemy along its path relative to the player, so neither of
+            // them can skip past the other in a single step.
+            let start_pos = pos;
+            let relative_motion = (vel - player_vel) * dt;
+            let reach = player_radius + radius;
+            let impact = collision::time_of_impact(start_pos, relative_motion, reach, player_pos);
+
+            // Update position, bouncing off of obstacles unless this enemy passes through them
+            match navigation {
+                Navigation::Ghost => pos += vel * dt,
+                Navigation::Bounce | Navigation::Pathfind => {
+                    let reach = vel.magnitude() * dt + radius * 2. + obstacle_motion;
+                    let (_, obstacles) =
+                        nearby_obstacles(&self.obstacle_grid, &self.obstacles, world, pos, reach);
+                    collision::sweep_circle(
+                        &mut pos,
+                        &mut vel,
+                        radius,
+                        &obstacles,
+                        physics.restitution,
+                        dt,
+                    );
+                }
+            }
+
+            if let Some(t) = impact {
+                if !self.contacts.contains(&entity) {
+                    hurt(life, invulnerable, config, &mut events);
+                }
+                contacts.insert(entity);
+                let impact_offset = player_pos - start_pos - relative_motion * t;
+                let contact = collision::circle_contact(player_pos, player_radius, pos, radius)
+                    .unwrap_or(collision::Contact {
+                        normal: impact_offset
+                            .try_normalize(f32::EPSILON)
+                            .unwrap_or_else(|| Vec2::new(0., 1.)),
+                        depth: 0.,
+                    });
+                enemy_contacts.push(contact);
+                vel *= -physics.enemy_recoil;
+            }
+
+            // Enemies play by the same rules at the edge as the player does
+            match apply_boundary(boundary, &mut pos, &mut vel, radius) {
+                Edge::Left => lost_enemies.push(entity),
+                Edge::Wrapped(offset) => {
+                    world.get_component_mut::<PrevPosition>(entity).unwrap().0 += offset;
+                }
+                Edge::Inside | Edge::Touched => {}
+            }
+
+            *world.get_component_mut::<Position>(entity).unwrap() = pos;
+            world.get_component_mut::<Velocity>(entity).unwrap().0 = vel;
+        }
+        for entity in lost_enemies {
+            world.delete(entity);
+        }
+
+        // Get shoved out of any enemy that caught us
+        let mut pos = player_pos;
+        let mut vel = player_vel;
+        for contact in &enemy_contacts {
+            pos += contact.normal * contact.depth;
+            vel = collision::bounce(vel, contact.normal, physics.restitution);
+        }
+
+        // Only the obstacles near where the player could get to this step are worth checking.  A
+        // bounce keeps at most `restitution` of the speed, which is never more than all of it, so
+        // nothing gets further than its speed would take it.
+        let reach = vel.magnitude() * dt + player_radius * 2. + obstacle_motion;
+        let (obstacle_entities, obstacles) =
+            nearby_obstacles(&self.obstacle_grid, &self.obstacles, world, pos, reach);
+
+        // Update position, bouncing off of every obstacle in the way
+        let sweep = collision::sweep_circle(
+            &mut pos,
+            &mut vel,
+            player_radius,
+            &obstacles,
+            physics.restitution,
+            dt,
+        );
+        for &i in &sweep.touched {
+            let entity = obstacle_entities[i];
+            // Colliding hurts
+            if !self.contacts.contains(&entity) {
+                hurt(life, invulnerable, config, &mut events);
+            }
+            contacts.insert(entity);
+        }
+
+        // Death (or at least a bruise) by edge?
+        let mut touching_edge = false;
+        let mut wrapped = Vec2::zeros();
+        match apply_boundary(boundary, &mut pos, &mut vel, player_radius) {
+            Edge::Left => dead = true,
+            Edge::Touched if boundary == Boundary::Painful => {
+                if !self.touching_edge {
+                    hurt(life, invulnerable, config, &mut events);
+                }
+                touching_edge = true;
+            }
+            Edge::Wrapped(offset) => wrapped = offset,
+            Edge::Inside | Edge::Touched => {}
+        }
+        for (mut player_pos, mut player_prev, mut player_vel) in
+            <(Write<Position>, Write<PrevPosition>, Write<Velocity>)>::query()
+                .filter(tag_value(&Player))
+                .iter_mut(world)
+        {
+            *player_pos = pos;
+            player_prev.0 += wrapped;
+            player_vel.0 = vel;
+        }
+
+        // Reached the goal?  Anywhere along the way counts, not just where we stopped.
+        if goals.iter().any(|&(goal_pos, goal_radius)| {
+            collision::path_touches(&sweep.path, (player_radius + goal_radius) / 3., goal_pos)
+        }) {
+            won = true;
+        }
+
+        // Close shaves.  Whatever is near the player now gets remembered, along with whether it
+        // ever touched, and whatever was near but isn't any more got away without touching.
+        let mut near: HashMap<Entity, bool> = HashMap::new();
+        for (&entity, body) in obstacle_entities.iter().zip(&obstacles) {
+            if body.shape.distance(body.pos_at(dt), pos) - player_radius < NEAR_MISS_MARGIN {
+                near.insert(entity, false);
+            }
+        }
+        for (entity, (enemy_pos, radius)) in <(Read<Position>, Read<Radius>)>::query()
+            .filter(tag_value(&Enemy))
+            .iter_entities(world)
+        {
+            if distance(&*enemy_pos, &pos) - player_radius - radius.0 < NEAR_MISS_MARGIN {
+                near.insert(entity, false);
+            }
+        }
+        for (entity, touched) in near.iter_mut() {
+            *touched = contacts.contains(entity) || self.near.get(entity) == Some(&true);
+        }
+        self.near_misses += self
+            .near
+            .iter()
+            .filter(|&(entity, &touched)| !touched && !near.contains_key(entity))
+            .count() as u32;
+        self.near = near;
+
+        self.contacts = contacts;
+        self.touching_edge = touching_edge;
+
+        if won {
+            events.push(GameEvent::Won);
+            self.over = true;
+            return events;
+        }
+
+        if dead || self.life <= 0 {
+            events.push(GameEvent::Died);
+            self.over = true;
+        }
+        events
+    }
+}
+
+/// What the edge of the arena did to something
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Edge {
+    /// Nowhere near it
+    Inside,
+    /// Bounced off of it
+    Touched,
+    /// Went out of the arena, never to return
+    Left,
+    /// Went out one side and came back in the other, moving this far
+    Wrapped(Vec2),
+}
+
+/// Keep a circle of `radius` at `pos`, moving at `vel`, in the arena however `boundary` says to
+fn apply_boundary(boundary: Boundary, pos: &mut Vec2, vel: &mut Vec2, radius: f32) -> Edge {
+    match boundary {
+        Boundary::Deadly => {
+            if pos.iter().any(|v| v.abs() > 1. + radius) {
+                Edge::Left
+            } else {
+                Edge::Inside
+            }
+        }
+        Boundary::Bouncy | Boundary::Painful => {
+            let limit = 1. - radius;
+            let mut edge = Edge::Inside;
+            for axis in 0..2 {
+                if pos[axis].abs() > limit {
+                    pos[axis] = pos[axis].clamp(-limit, limit);
+                    // Send it back the way it came, no matter how it hit
+                    if vel[axis] * pos[axis] > 0. {
+                        vel[axis] = -vel[axis];
+                    }
+                    edge = Edge::Touched;
+                }
+            }
+            edge
+        }
+        Boundary::Wrap => {
+            let before = *pos;
+            for axis in 0..2 {
+                if pos[axis] > 1. {
+                    pos[axis] -= 2.;
+                } else if pos[axis] < -1. {
+                    pos[axis] += 2.;
+                }
+            }
+            if *pos == before {
+                Edge::Inside
+            } else {
+                Edge::Wrapped(*pos - before)
+            }
+        }
+    }
+}
+
+/// The shape of an obstacle: a circle if it has a `Radius`, or a `Rectangle`
+fn obstacle_shape(world: &World, entity: Entity) -> Option<collision::Shape> {
+    if let Some(radius) = world.get_component::<Radius>(entity) {
+        return Some(collision::Shape::Circle { radius: radius.0 });
+    }
+    let rectangle = world.get_component::<Rectangle>(entity)?;
+    Some(collision::Shape::Rect {
+        half_size: rectangle.half_size,
+        angle: rectangle.angle,
+    })
+}
+
+/// A broadphase over `obstacles` where they are now, and a grid for enemies with radius
+/// `nav_clearance` to find their way around them
+fn build_grids(world: &World, obstacles: &[Entity], nav_clearance: f32) -> (SpatialHash, NavGrid) {
+    let bodies: Vec<collision::Body> = obstacles
+        .iter()
+        .filter_map(|&entity| {
+            Some(collision::Body {
+                pos: *world.get_component::<Position>(entity)?,
+                vel: Vec2::zeros(),
+                shape: obstacle_shape(world, entity)?,
+            })
+        })
+        .collect();
+    let circles: Vec<(Position, f32)> = bodies
+        .iter()
+        .map(|body| (body.pos, body.shape.bounding_radius()))
+        .collect();
+    (
+        SpatialHash::from_circles(DEFAULT_CELL_SIZE, &circles),
+        NavGrid::from_bodies(&bodies, nav_clearance),
+    )
+}
+
+/// The obstacles (entity, and where and how fast it moved this step) that might be within `reach`
+/// of `pos`
+fn nearby_obstacles(
+    grid: &SpatialHash,
+    obstacles: &[Entity],
+    world: &World,
+    pos: Position,
+    reach: f32,
+) -> (Vec<Entity>, Vec<collision::Body>) {
+    grid.query(pos, reach)
+        .into_iter()
+        .filter_map(|i| {
+            let entity = obstacles[i];
+            // Walls never move, so they don't bother remembering where they were
+            let start = match world.get_component::<PrevPosition>(entity) {
+                Some(prev) => prev.0,
+                None => *world.get_component::<Position>(entity)?,
+            };
+            let vel = world
+                .get_component::<Velocity>(entity)
+                .map_or_else(Vec2::zeros, |vel| vel.0);
+            let body = collision::Body {
+                pos: start,
+                vel,
+                shape: obstacle_shape(world, entity)?,
+            };
+            Some((entity, body))
+        })
+        .unzip()
+}
+
+/// Take a life from the player, unless they're still invulnerable from the last hit
+fn hurt(life: &mut i32, invulnerable: &mut f32, config: &GameConfig, events: &mut Vec<GameEvent>) {
+    if *invulnerable > 0. {
+        return;
+    }
+    *life -= 1;
+    *invulnerable = config.invulnerability;
+    events.push(GameEvent::Hit { life: *life });
+}
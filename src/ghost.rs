@@ -0,0 +1,98 @@
+//! Racing against your own best run.  The fastest win on each level is kept as a replay, and a
+//! `Ghost` plays the player's path from it back alongside the live run.
+
+use crate::level::Level;
+use crate::replay::Replay;
+use crate::storage;
+use crate::timestep::TICK_RATE;
+use rusty_core::glm::{distance2, Vec2};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Where the player was on every tick of a recorded run
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ghost {
+    /// The player's position before the first tick, then after each one
+    path: Vec<Vec2>,
+}
+
+impl Ghost {
+    /// Play `replay` through to find the path its player took
+    pub fn from_replay(replay: &Replay) -> Self {
+        let mut game = replay.game();
+        let mut playback = replay.playback();
+        let mut path = vec![game.player_pos()];
+        while playback.step(&mut game).is_some() {
+            path.push(game.player_pos());
+        }
+        Self { path }
+    }
+
+    /// How many ticks the run lasted
+    pub fn ticks(&self) -> u32 {
+        self.path.len() as u32 - 1
+    }
+
+    /// Where the ghost is after `tick` ticks.  Once its run is over, it stays where it finished.
+    pub fn pos_at(&self, tick: u32) -> Vec2 {
+        let last = self.path.len() - 1;
+        self.path[(tick as usize).min(last)]
+    }
+
+    /// Seconds the live run is behind the ghost, when the live player is at `pos` after `tick`
+    /// ticks.  Negative means ahead.  This compares against when the ghost came closest to `pos`,
+    /// so going a different way than the ghost did gives a rough answer at best.
+    pub fn split(&self, tick: u32, pos: Vec2) -> f32 {
+        let ghost_tick = self
+            .path
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| distance2(a, &pos).total_cmp(&distance2(b, &pos)))
+            .map(|(i, _)| i as u32)
+            .unwrap_or(0);
+        (tick as f32 - ghost_tick as f32) / TICK_RATE as f32
+    }
+}
+
+/// The best winning run on each level, one replay file per level in a directory
+#[derive(Clone, Debug)]
+pub struct BestRuns {
+    dir: PathBuf,
+}
+
+impl BestRuns {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Where best runs are kept by default
+    pub fn default_dir() -> Option<PathBuf> {
+        storage::data_dir().map(|dir| dir.join("ghosts"))
+    }
+
+    /// The file the best run on `level` is kept in
+    pub fn path(&self, level: &Level) -> PathBuf {
+        self.dir.join(format!("{}.json", level.key()))
+    }
+
+    /// The best winning run on `level` so far, if there is one that can still be played back
+    pub fn best(&self, level: &Level) -> Option<Replay> {
+        Replay::load(self.path(level)).ok()
+    }
+
+    /// Keep `replay`, a winning run, if it's faster than the best so far.  Returns whether it was.
+    pub fn offer(&self, replay: &Replay) -> io::Result<bool> {
+        let path = self.path(&replay.level);
+        if let Some(best) = self.best(&replay.level) {
+            if best.ticks() <= replay.ticks() {
+                return Ok(false);
+            }
+        }
+        replay.save(&path)?;
+        Ok(true)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
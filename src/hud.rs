@@ -0,0 +1,364 @@
+//! What's written on top of the arena: the timer, lives, level name, seed and score, the banners
+//! for winning, dying, pausing and the title screen, and the high scores under the winning one.
+//!
+//! Text is drawn in a blocky 5x7 bitmap font.  Everything here is plain layout, in the same
+//! coordinates as the arena (-1.0 to 1.0, y up), so the frontend only has to draw the rectangles
+//! it's handed.
+
+use crate::components::Tint;
+use crate::game::Game;
+use crate::score::{Score, TABLE_SIZE};
+use crate::session::State;
+use crate::LIFE_CIRCLE_RADIUS;
+use rusty_core::glm::Vec2;
+
+/// Size of one pixel of the font in HUD text
+pub const PIXEL: f32 = 1. / 128.;
+/// Size of one pixel of the font in banners, unless that would make them too wide to fit
+pub const BANNER_PIXEL: f32 = 1. / 32.;
+/// How much of the arena's width a line of text can take up before it's shrunk to fit
+const MAX_WIDTH: f32 = 1.9;
+
+/// How many of the best scores are listed when the player wins
+const WON_ROWS: usize = 5;
+const WON_TITLE: &str = "YOU WIN!";
+/// What to do after a win in free play
+pub const PLAY_AGAIN: &str = "ENTER TO PLAY AGAIN   TAB FOR A NEW LAYOUT";
+/// What to do after a win when there's more to come, like the rest of a campaign
+pub const CARRY_ON: &str = "PRESS ANY KEY TO CARRY ON";
+
+/// Glyphs are this many pixels wide...
+const GLYPH_WIDTH: usize = 5;
+/// ...and this many tall
+const GLYPH_HEIGHT: usize = 7;
+/// Pixels from the start of one character to the start of the next
+const ADVANCE: usize = GLYPH_WIDTH + 1;
+/// Pixels from the top of one line to the top of the next
+const LINE_HEIGHT: usize = GLYPH_HEIGHT + 3;
+
+/// Where a label's position is along its width
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// A line of text
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    pub text: String,
+    /// The top of the text, and its left edge, center or right edge depending on `align`
+    pub pos: Vec2,
+    /// Size of one pixel of the font
+    pub pixel: f32,
+    pub align: Align,
+    pub tint: Tint,
+}
+
+/// A filled rectangle, which is what text is drawn with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Block {
+    pub center: Vec2,
+    pub half_size: Vec2,
+}
+
+impl Label {
+    pub fn new(text: impl Into<String>, pos: Vec2, pixel: f32, align: Align) -> Self {
+        Self {
+            text: text.into(),
+            pos,
+            pixel,
+            align,
+            tint: Tint::TEXT,
+        }
+    }
+
+    /// How wide the text is, from the left of the first character to the right of the last
+    pub fn width(&self) -> f32 {
+        let chars = self.text.chars().count();
+        (chars * ADVANCE).saturating_sub(ADVANCE - GLYPH_WIDTH) as f32 * self.pixel
+    }
+
+    /// How tall the text is
+    pub fn height(&self) -> f32 {
+        GLYPH_HEIGHT as f32 * self.pixel
+    }
+
+    /// The rectangles to fill to draw the text.  Lit pixels next to each other in a row are
+    /// merged into one rectangle, so there are far fewer of these than pixels.
+    pub fn blocks(&self) -> Vec<Block> {
+        let left = match self.align {
+            Align::Left => self.pos[0],
+            Align::Center => self.pos[0] - self.width() / 2.,
+            Align::Right => self.pos[0] - self.width(),
+        };
+        let top = self.pos[1];
+        let mut blocks = vec![];
+        for (i, c) in self.text.chars().enumerate() {
+            let x = left + (i * ADVANCE) as f32 * self.pixel;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                let y = top - (row as f32 + 0.5) * self.pixel;
+                let mut col = 0;
+                while col < GLYPH_WIDTH {
+                    if !lit(*bits, col) {
+                        col += 1;
+                        continue;
+                    }
+                    let start = col;
+                    while col < GLYPH_WIDTH && lit(*bits, col) {
+                        col += 1;
+                    }
+                    let run = (col - start) as f32 * self.pixel;
+                    blocks.push(Block {
+                        center: Vec2::new(x + start as f32 * self.pixel + run / 2., y),
+                        half_size: Vec2::new(run / 2., self.pixel / 2.),
+                    });
+                }
+            }
+        }
+        blocks
+    }
+}
+
+/// Everything to write over `game` while the session is in `state`
+pub fn labels(game: &Game, state: State) -> Vec<Label> {
+    let mut labels = status(game);
+    let difficulty = game.config().difficulty.to_string();
+    let (title, subtitle) = match state {
+        State::Title => (
+            "CIRCLE GAUNTLET".to_string(),
+            format!("SPACE TO START   +/- DIFFICULTY: {}", difficulty),
+        ),
+        State::Playing => return labels,
+        State::Paused => (
+            "PAUSED".to_string(),
+            "SPACE TO RESUME\nENTER TO RESTART   TAB FOR A NEW LAYOUT".to_string(),
+        ),
+        State::Won => return win_screen(game, PLAY_AGAIN, None),
+        State::Died => (
+            "YOU DIED!".to_string(),
+            "ENTER TO TRY AGAIN   TAB FOR A NEW LAYOUT".to_string(),
+        ),
+    };
+    labels.extend(banner(&title, &subtitle));
+    labels
+}
+
+/// Everything to write over `game` once it's been won, whatever comes next: the winning banner,
+/// with `subtitle` saying how to go on, and under it the layout's high score table and where the
+/// win placed in it, if it was recorded
+pub fn win_screen(
+    game: &Game,
+    subtitle: &str,
+    standing: Option<(&[Score], Option<usize>)>,
+) -> Vec<Label> {
+    let mut labels = status(game);
+    labels.extend(banner(WON_TITLE, subtitle));
+    if let Some((table, rank)) = standing {
+        labels.extend(high_scores(table, rank));
+    }
+    labels
+}
+
+/// The timer, lives, level name, seed and score, which are up whatever state the session is in
+fn status(game: &Game) -> Vec<Label> {
+    let margin = PIXEL * 2.;
+    let top = 1. - margin;
+    let bottom = -1. + margin + GLYPH_HEIGHT as f32 * PIXEL;
+    vec![
+        // Lives as a number, after the row of icons
+        Label::new(
+            game.life().to_string(),
+            Vec2::new(
+                -1. + LIFE_CIRCLE_RADIUS * 2. * game.life().max(0) as f32 + margin,
+                top,
+            ),
+            PIXEL,
+            Align::Left,
+        ),
+        Label::new(
+            game.level().name.clone(),
+            Vec2::new(0., top),
+            PIXEL,
+            Align::Center,
+        ),
+        Label::new(
+            format!("{:.2}", game.time()),
+            Vec2::new(1. - margin, top),
+            PIXEL,
+            Align::Right,
+        ),
+        Label::new(
+            format!("SEED {}", game.seed()),
+            Vec2::new(-1. + margin, bottom),
+            PIXEL,
+            Align::Left,
+        ),
+        Label::new(
+            format!("SCORE {}", live_score(game)),
+            Vec2::new(1. - margin, bottom),
+            PIXEL,
+            Align::Right,
+        ),
+    ]
+}
+
+/// A big line of text across the middle of the arena, with smaller lines (split on `\n`) under it.
+/// Lines too long to fit across the arena are shrunk until they do.
+pub fn banner(title: &str, subtitle: &str) -> Vec<Label> {
+    let title = Label::new(
+        title,
+        Vec2::new(0., GLYPH_HEIGHT as f32 * BANNER_PIXEL / 2.),
+        fit(title, BANNER_PIXEL),
+        Align::Center,
+    );
+    let mut top = title.pos[1] - title.height() - (LINE_HEIGHT - GLYPH_HEIGHT) as f32 * PIXEL * 2.;
+    let mut labels = vec![title];
+    for line in subtitle.lines() {
+        let label = Label::new(line, Vec2::new(0., top), fit(line, PIXEL), Align::Center);
+        top -= LINE_HEIGHT as f32 * label.pixel;
+        labels.push(label);
+    }
+    labels
+}
+
+/// The high score table for the layout just won, to go under the winning banner: where the run
+/// placed (`rank`, 0 being the top), then the best few scores.  The run's own row is picked out in
+/// green, and added at the bottom if it placed lower than those.
+pub fn high_scores(table: &[Score], rank: Option<usize>) -> Vec<Label> {
+    // Both ways of going on after a win are a single line, so the banner ends in the same place
+    let mut top = banner(WON_TITLE, PLAY_AGAIN)
+        .last()
+        .map_or(0., |label| label.pos[1] - label.height())
+        - LINE_HEIGHT as f32 * PIXEL * 2.;
+    let mut line = |text: String| {
+        let pixel = fit(&text, PIXEL);
+        let label = Label::new(text, Vec2::new(0., top), pixel, Align::Center);
+        top -= LINE_HEIGHT as f32 * label.pixel;
+        label
+    };
+    let mut labels = vec![line(match rank {
+        Some(rank) => format!("#{} ON THIS LAYOUT", rank + 1),
+        None => format!("NOT IN THE TOP {} ON THIS LAYOUT", TABLE_SIZE),
+    })];
+    let rows = table
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i < WON_ROWS || Some(i) == rank);
+    for (i, score) in rows {
+        // Padded to the same width, so the columns line up when centered
+        let mut label = line(format!(
+            "{:2}. {:6}  {:6.2}S  {:9}",
+            i + 1,
+            score.points,
+            score.seconds,
+            score.difficulty.to_string()
+        ));
+        if Some(i) == rank {
+            label.tint = Tint::GOAL;
+        }
+        labels.push(label);
+    }
+    labels
+}
+
+/// The biggest pixel size up to `pixel` that keeps `text` within `MAX_WIDTH`
+fn fit(text: &str, pixel: f32) -> f32 {
+    let width = Label::new(text, Vec2::zeros(), 1., Align::Center).width();
+    if width * pixel > MAX_WIDTH {
+        MAX_WIDTH / width
+    } else {
+        pixel
+    }
+}
+
+/// Where to draw each of the player's life icons, along the top left
+pub fn life_icons(game: &Game) -> Vec<Vec2> {
+    (0..game.life().max(0))
+        .map(|i| {
+            Vec2::new(
+                -1.0 + LIFE_CIRCLE_RADIUS + (2.0 * i as f32 * LIFE_CIRCLE_RADIUS),
+                1.0 - LIFE_CIRCLE_RADIUS,
+            )
+        })
+        .collect()
+}
+
+/// Where the split-time bar goes: just under the timer in the top right
+pub fn split_bar_top() -> f32 {
+    1. - PIXEL * 2. - LINE_HEIGHT as f32 * PIXEL
+}
+
+/// What the player would score if they won right now
+fn live_score(game: &Game) -> u32 {
+    Score::new(
+        game.time(),
+        game.life(),
+        game.near_misses(),
+        game.config().difficulty,
+    )
+    .points
+}
+
+fn lit(bits: u8, col: usize) -> bool {
+    bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0
+}
+
+/// The rows of `c`, top first, with the leftmost pixel in the highest of the five bits.
+/// Lowercase letters are drawn as capitals, and anything else the font doesn't have as `?`.
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        'A' => [0x0e, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+        'B' => [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e],
+        'C' => [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e],
+        'D' => [0x1e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1e],
+        'E' => [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f],
+        'F' => [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10],
+        'G' => [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f],
+        'H' => [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+        'I' => [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f],
+        'M' => [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+        'P' => [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10],
+        'Q' => [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d],
+        'R' => [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11],
+        'S' => [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e],
+        'T' => [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a],
+        'X' => [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0a, 0x04, 0x04, 0x04],
+        'Z' => [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f],
+        '0' => [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e],
+        '1' => [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        '2' => [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f],
+        '3' => [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e],
+        '4' => [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02],
+        '5' => [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e],
+        '6' => [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e],
+        '7' => [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e],
+        '9' => [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c],
+        ' ' => [0x00; GLYPH_HEIGHT],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0c, 0x04, 0x08],
+        ':' => [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x0c, 0x00],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+        '-' => [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00],
+        '+' => [0x00, 0x04, 0x04, 0x1f, 0x04, 0x04, 0x00],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '\'' => [0x04, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '#' => [0x0a, 0x0a, 0x1f, 0x0a, 0x1f, 0x0a, 0x0a],
+        '%' => [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1f],
+        _ => [0x0e, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
@@ -0,0 +1,678 @@
+//! Level descriptions, either loaded from a TOML file or generated from a seed.
+//!
+//! A level file looks like this (everything but `name`, `player_start` and at least one `[[goal]]`
+//! is optional):
+//!
+//! ```toml
+//! name = "First Steps"
+//! author = "Nathan Stocks"
+//! par_time = 8.0
+//! player_start = [-0.75, 0.75]
+//! player_radius = 0.0625
+//! boundary = "bouncy"
+//!
+//! [[goal]]
+//! pos = [0.75, -0.75]
+//!
+//! [[obstacle]]
+//! pos = [0.0, 0.0]
+//! radius = 0.15
+//!
+//! [[obstacle]]
+//! pos = [-0.5, 0.0]
+//! motion = { type = "ping_pong", to = [-0.5, -0.5], period = 4.0 }
+//! pulse = { radius = 0.12, period = 2.0 }
+//!
+//! [[wall]]
+//! type = "segment"
+//! from = [0.25, -1.0]
+//! to = [0.25, 0.25]
+//!
+//! [[wall]]
+//! type = "rectangle"
+//! center = [-0.5, -0.5]
+//! size = [0.3, 0.1]
+//! angle = 45.0
+//!
+//! [[enemy]]
+//! type = "chaser"
+//! spawn = [0.75, 0.75]
+//! radius = 0.0625
+//! ```
+//!
+//! Every `radius` (and `player_radius`) can be left out to get the usual size for that kind of
+//! thing.
+//!
+//! Obstacles can move with `motion`: `ping_pong` (back and forth to `to`), `orbit` (around
+//! `center`) or `bounce` (at `velocity`, off of the arena's edges).  They can also grow and shrink
+//! with `pulse`.
+//!
+//! The `boundary` says what happens at the edges of the arena: `deadly` (the default) kills
+//! anything that leaves, `bouncy` walls it in, `wrap` brings it back in on the opposite side, and
+//! `painful` walls it in but costs the player a life every time they touch it.
+//!
+//! Walls are either a `segment` (a straight line `thickness` across, between `from` and `to`) or a
+//! `rectangle` (`size` is width and height, and `angle` is degrees counter-clockwise).
+//!
+//! Enemy types are `chaser`, `interceptor`, `patroller` (which needs `waypoints = [[x, y], ...]`),
+//! `ambusher` (with an optional `range`) and `goal_guard` (with an optional `orbit` radius).  Any
+//! enemy can also say how it handles obstacles with `navigation`: `ghost` passes through them,
+//! `bounce` (the default) bounces off of them, and `pathfind` finds a way around them.
+
+use crate::collision::Shape;
+use crate::components::Position;
+use crate::game::GameConfig;
+use crate::solver;
+use crate::spatial::SpatialHash;
+use crate::storage::{self, FileError};
+use crate::{ENEMY_WIDTH, GOAL_RADIUS, OBSTACLE_RADIUS, PLAYER_RADIUS, WALL_THICKNESS};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use rusty_core::glm::{distance2, Vec2};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// How many layouts `Level::generate` rolls looking for a solvable one before clearing a way
+/// through the last one.  Crowded enough configs may never produce one.
+const GENERATE_ATTEMPTS: usize = 20;
+/// How many layouts in a row with no gap at all it takes to decide that the arena is too crowded
+/// for rolling again to help
+const SEALED_ATTEMPTS: usize = 3;
+/// How many random spots to try for each obstacle or enemy before giving up on fitting it in
+const PLACEMENT_TRIES: usize = 10_000;
+
+/// Everything needed to lay out the arena at the start of a game
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Level {
+    pub name: String,
+    #[serde(default)]
+    pub author: String,
+    /// How many seconds a good run should take
+    #[serde(default)]
+    pub par_time: Option<f32>,
+    pub player_start: [f32; 2],
+    #[serde(default = "default_player_radius")]
+    pub player_radius: f32,
+    #[serde(default)]
+    pub boundary: Boundary,
+    #[serde(rename = "goal")]
+    pub goals: Vec<GoalSpec>,
+    #[serde(default, rename = "obstacle")]
+    pub obstacles: Vec<ObstacleSpec>,
+    #[serde(default, rename = "wall")]
+    pub walls: Vec<WallSpec>,
+    #[serde(default, rename = "enemy")]
+    pub enemies: Vec<EnemySpec>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct GoalSpec {
+    pub pos: [f32; 2],
+    #[serde(default = "default_goal_radius")]
+    pub radius: f32,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObstacleSpec {
+    pub pos: [f32; 2],
+    #[serde(default = "default_obstacle_radius")]
+    pub radius: f32,
+    /// How it moves, if it moves at all
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub motion: Option<Motion>,
+    /// How its size changes, if it does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pulse: Option<Pulse>,
+}
+
+/// The ways an obstacle can move, starting from its `pos`
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Motion {
+    /// Back and forth in a straight line between `pos` and `to`, taking `period` seconds for the
+    /// round trip
+    PingPong { to: [f32; 2], period: f32 },
+    /// Around `center`, staying as far from it as `pos` is, taking `period` seconds for each lap.
+    /// Counter-clockwise, or clockwise if `period` is negative.
+    Orbit { center: [f32; 2], period: f32 },
+    /// In a straight line at `velocity`, bouncing off of the edges of the arena
+    Bounce { velocity: [f32; 2] },
+}
+
+/// An obstacle that grows and shrinks
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pulse {
+    /// The size it grows (or shrinks) to, from its usual `radius` and back again
+    pub radius: f32,
+    /// Seconds to get there and back
+    pub period: f32,
+}
+
+/// A straight-sided obstacle
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum WallSpec {
+    /// A straight line from `from` to `to`
+    Segment {
+        from: [f32; 2],
+        to: [f32; 2],
+        #[serde(default = "default_wall_thickness")]
+        thickness: f32,
+    },
+    /// A rectangle `size` (width, height) across, turned `angle` degrees counter-clockwise
+    Rectangle {
+        center: [f32; 2],
+        size: [f32; 2],
+        #[serde(default)]
+        angle: f32,
+    },
+}
+
+impl WallSpec {
+    /// The wall as a rectangle: its center, how far it reaches from there to each side, and how
+    /// far it's turned counter-clockwise in radians
+    pub fn rectangle(&self) -> (Vec2, Vec2, f32) {
+        match *self {
+            WallSpec::Segment {
+                from,
+                to,
+                thickness,
+            } => {
+                let (from, to) = (Vec2::from(from), Vec2::from(to));
+                let along = to - from;
+                (
+                    (from + to) / 2.,
+                    Vec2::new(along.magnitude(), thickness) / 2.,
+                    along[1].atan2(along[0]),
+                )
+            }
+            WallSpec::Rectangle {
+                center,
+                size,
+                angle,
+            } => (
+                Vec2::from(center),
+                Vec2::from(size) / 2.,
+                angle.to_radians(),
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnemySpec {
+    #[serde(default, rename = "type")]
+    pub kind: EnemyKind,
+    pub spawn: [f32; 2],
+    #[serde(default = "default_enemy_radius")]
+    pub radius: f32,
+    /// Patrollers: the points to walk between, in order, looping back to the first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub waypoints: Vec<[f32; 2]>,
+    /// Ambushers: how close the player has to come before it springs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<f32>,
+    /// Goal guards: how far from the goal to circle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orbit: Option<f32>,
+    /// How it deals with obstacles in its way
+    #[serde(default)]
+    pub navigation: Navigation,
+}
+
+impl Default for EnemySpec {
+    fn default() -> Self {
+        Self {
+            kind: EnemyKind::default(),
+            spawn: [0., 0.],
+            radius: default_enemy_radius(),
+            waypoints: vec![],
+            range: None,
+            orbit: None,
+            navigation: Navigation::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnemyKind {
+    /// Accelerates straight at the player
+    #[default]
+    Chaser,
+    /// Leads the player, heading for where they're going to be
+    Interceptor,
+    /// Walks between `waypoints`, ignoring the player
+    Patroller,
+    /// Waits until the player comes within `range`, then chases
+    Ambusher,
+    /// Circles the goal at a distance of `orbit`
+    GoalGuard,
+}
+
+/// What happens to the player and enemies at the edges of the arena
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Boundary {
+    /// Leaving the arena is fatal
+    #[default]
+    Deadly,
+    /// The edges bounce things back without slowing them down
+    Bouncy,
+    /// Going off one edge comes back in at the opposite one
+    Wrap,
+    /// The edges bounce things back, and touching them costs the player a life
+    Painful,
+}
+
+/// How an enemy deals with obstacles
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Navigation {
+    /// Passes straight through them
+    Ghost,
+    /// Bounces off of them, just like the player
+    #[default]
+    Bounce,
+    /// Bounces off of them, and finds a way around them to wherever it's going
+    Pathfind,
+}
+
+fn default_goal_radius() -> f32 {
+    GOAL_RADIUS
+}
+
+fn default_obstacle_radius() -> f32 {
+    OBSTACLE_RADIUS
+}
+
+fn default_wall_thickness() -> f32 {
+    WALL_THICKNESS
+}
+
+fn default_player_radius() -> f32 {
+    PLAYER_RADIUS
+}
+
+fn default_enemy_radius() -> f32 {
+    ENEMY_WIDTH * 0.5
+}
+
+impl Level {
+    /// Load and validate a level file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FileError> {
+        storage::load_file(path.as_ref(), Self::parse)
+    }
+
+    /// Parse and validate the contents of a level file
+    pub fn parse(text: &str) -> Result<Self, FileError> {
+        let level: Level = toml::from_str(text).map_err(|err| FileError::Parse {
+            path: PathBuf::new(),
+            kind: "level",
+            message: err.to_string().trim_end().to_string(),
+        })?;
+        level.validate()?;
+        Ok(level)
+    }
+
+    /// A name for this exact layout, for keeping things like best runs and high scores apart.
+    /// It's the level's name plus a hash of everything in it, so an edited level file or a
+    /// different generated layout gets a fresh key.  The same layout gets the same key from one
+    /// run of the game to the next.
+    pub fn key(&self) -> String {
+        let slug: String = self
+            .name
+            .chars()
+            .map(|c| match c {
+                c if c.is_ascii_alphanumeric() => c.to_ascii_lowercase(),
+                _ => '_',
+            })
+            .collect();
+        // FNV-1a, since the standard library's hasher isn't promised to stay the same
+        let text = serde_json::to_string(self).unwrap_or_default();
+        let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash: u64, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+        format!("{}-{:016x}", slug, hash)
+    }
+
+    /// Check the values that TOML can't check for us
+    pub fn validate(&self) -> Result<(), FileError> {
+        let invalid = |field: String, message: &str| FileError::Invalid {
+            path: PathBuf::new(),
+            field,
+            message: message.to_string(),
+        };
+        let in_arena = |pos: &[f32; 2]| pos.iter().all(|v| v.is_finite() && v.abs() <= 1.);
+        let positive = |radius: f32| radius.is_finite() && radius > 0.;
+
+        if !in_arena(&self.player_start) {
+            return Err(invalid(
+                "player_start".into(),
+                "must be inside the arena, [-1.0, 1.0] on both axes",
+            ));
+        }
+        if !positive(self.player_radius) {
+            return Err(invalid("player_radius".into(), "must be positive"));
+        }
+        if self.goals.is_empty() {
+            return Err(invalid("goal".into(), "a level needs at least one goal"));
+        }
+        for (i, goal) in self.goals.iter().enumerate() {
+            if !in_arena(&goal.pos) {
+                return Err(invalid(
+                    format!("goal[{}].pos", i),
+                    "must be inside the arena, [-1.0, 1.0] on both axes",
+                ));
+            }
+            if !positive(goal.radius) {
+                return Err(invalid(format!("goal[{}].radius", i), "must be positive"));
+            }
+        }
+        for (i, obstacle) in self.obstacles.iter().enumerate() {
+            if !in_arena(&obstacle.pos) {
+                return Err(invalid(
+                    format!("obstacle[{}].pos", i),
+                    "must be inside the arena, [-1.0, 1.0] on both axes",
+                ));
+            }
+            if !positive(obstacle.radius) {
+                return Err(invalid(
+                    format!("obstacle[{}].radius", i),
+                    "must be positive",
+                ));
+            }
+            match obstacle.motion {
+                Some(Motion::PingPong { to, period }) => {
+                    if !in_arena(&to) {
+                        return Err(invalid(
+                            format!("obstacle[{}].motion.to", i),
+                            "must be inside the arena, [-1.0, 1.0] on both axes",
+                        ));
+                    }
+                    if !positive(period) {
+                        return Err(invalid(
+                            format!("obstacle[{}].motion.period", i),
+                            "must be positive",
+                        ));
+                    }
+                }
+                Some(Motion::Orbit { center, period }) => {
+                    if !in_arena(&center) {
+                        return Err(invalid(
+                            format!("obstacle[{}].motion.center", i),
+                            "must be inside the arena, [-1.0, 1.0] on both axes",
+                        ));
+                    }
+                    if !period.is_finite() || period == 0. {
+                        return Err(invalid(
+                            format!("obstacle[{}].motion.period", i),
+                            "must not be zero",
+                        ));
+                    }
+                }
+                Some(Motion::Bounce { velocity }) if !velocity.iter().all(|v| v.is_finite()) => {
+                    return Err(invalid(
+                        format!("obstacle[{}].motion.velocity", i),
+                        "must be a finite number",
+                    ));
+                }
+                Some(Motion::Bounce { .. }) | None => {}
+            }
+            if let Some(pulse) = obstacle.pulse {
+                if !positive(pulse.radius) {
+                    return Err(invalid(
+                        format!("obstacle[{}].pulse.radius", i),
+                        "must be positive",
+                    ));
+                }
+                if !positive(pulse.period) {
+                    return Err(invalid(
+                        format!("obstacle[{}].pulse.period", i),
+                        "must be positive",
+                    ));
+                }
+            }
+        }
+        for (i, wall) in self.walls.iter().enumerate() {
+            match *wall {
+                WallSpec::Segment {
+                    from,
+                    to,
+                    thickness,
+                } => {
+                    for (name, end) in &[("from", from), ("to", to)] {
+                        if !in_arena(end) {
+                            return Err(invalid(
+                                format!("wall[{}].{}", i, name),
+                                "must be inside the arena, [-1.0, 1.0] on both axes",
+                            ));
+                        }
+                    }
+                    if from == to {
+                        return Err(invalid(
+                            format!("wall[{}].to", i),
+                            "must be somewhere other than `from`",
+                        ));
+                    }
+                    if !positive(thickness) {
+                        return Err(invalid(
+                            format!("wall[{}].thickness", i),
+                            "must be positive",
+                        ));
+                    }
+                }
+                WallSpec::Rectangle {
+                    center,
+                    size,
+                    angle,
+                } => {
+                    if !in_arena(&center) {
+                        return Err(invalid(
+                            format!("wall[{}].center", i),
+                            "must be inside the arena, [-1.0, 1.0] on both axes",
+                        ));
+                    }
+                    if !size.iter().all(|&side| positive(side)) {
+                        return Err(invalid(format!("wall[{}].size", i), "must be positive"));
+                    }
+                    if !angle.is_finite() {
+                        return Err(invalid(
+                            format!("wall[{}].angle", i),
+                            "must be a finite number",
+                        ));
+                    }
+                }
+            }
+        }
+        for (i, enemy) in self.enemies.iter().enumerate() {
+            if !in_arena(&enemy.spawn) {
+                return Err(invalid(
+                    format!("enemy[{}].spawn", i),
+                    "must be inside the arena, [-1.0, 1.0] on both axes",
+                ));
+            }
+            if !positive(enemy.radius) {
+                return Err(invalid(format!("enemy[{}].radius", i), "must be positive"));
+            }
+            if enemy.kind == EnemyKind::Patroller && enemy.waypoints.is_empty() {
+                return Err(invalid(
+                    format!("enemy[{}].waypoints", i),
+                    "a patroller needs at least one waypoint",
+                ));
+            }
+            for (j, waypoint) in enemy.waypoints.iter().enumerate() {
+                if !in_arena(waypoint) {
+                    return Err(invalid(
+                        format!("enemy[{}].waypoints[{}]", i, j),
+                        "must be inside the arena, [-1.0, 1.0] on both axes",
+                    ));
+                }
+            }
+            if enemy.range.is_some_and(|range| !positive(range)) {
+                return Err(invalid(format!("enemy[{}].range", i), "must be positive"));
+            }
+            if enemy.orbit.is_some_and(|orbit| !positive(orbit)) {
+                return Err(invalid(format!("enemy[{}].orbit", i), "must be positive"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Randomly generate the classic layout: player in the top left, goal in the bottom right, and
+    /// obstacles scattered in between.  The same `config.seed` always produces the same level.
+    /// Layouts the player can't squeeze through are thrown away and rolled again.  If that doesn't
+    /// turn up one they can, obstacles are cleared out of the way until it does, so the level
+    /// always has fewer obstacles than asked for then.
+    pub fn generate(config: &GameConfig) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+        let mut level = Self::scatter(config, &mut rng);
+        let mut sealed = 0;
+        for attempt in 1..=GENERATE_ATTEMPTS {
+            let analysis = solver::analyze(&level);
+            if analysis.solvable {
+                return level;
+            }
+            sealed = if analysis.narrowest == Some(0.) {
+                sealed + 1
+            } else {
+                0
+            };
+            if attempt == GENERATE_ATTEMPTS || sealed == SEALED_ATTEMPTS {
+                break;
+            }
+            level = Self::scatter(config, &mut rng);
+        }
+        level.clear_path();
+        level
+    }
+
+    /// Take out every obstacle in a straight corridor from the player's start to the first goal,
+    /// leaving room for two players side by side all the way along it
+    fn clear_path(&mut self) {
+        let start = Vec2::new(self.player_start[0], self.player_start[1]);
+        let goal = Vec2::new(self.goals[0].pos[0], self.goals[0].pos[1]);
+        let along = goal - start;
+        // A rectangle with no width is the line from the start to the goal
+        let corridor = Shape::Rect {
+            half_size: Vec2::new(along.magnitude() / 2., 0.),
+            angle: along[1].atan2(along[0]),
+        };
+        let center = (start + goal) / 2.;
+        let room = self.player_radius * 2.;
+        self.obstacles.retain(|obstacle| {
+            let pos = Vec2::new(obstacle.pos[0], obstacle.pos[1]);
+            corridor.distance(center, pos) >= obstacle.radius + room
+        });
+    }
+
+    /// One attempt at a generated layout, which may or may not be solvable.  Only placement,
+    /// without any of `generate`'s checking, which is handy for timing it.
+    pub fn scatter(config: &GameConfig, rng: &mut ChaCha8Rng) -> Self {
+        let goal_pos = Position::new(0.75, -0.75);
+        let player_start_pos = Position::new(-0.75, 0.75);
+
+        // Obstacle starting places.  The grid keeps checking spacing cheap with lots of obstacles.
+        let mut prev_positions = vec![];
+        let mut grid = SpatialHash::default();
+        let keep_clear = [player_start_pos, goal_pos];
+        let mut obstacles = vec![];
+        for _ in 0..config.obstacle_count {
+            let spot = find_spot(
+                rng,
+                (&keep_clear, config.obstacle_spacing),
+                (&prev_positions, &grid, config.obstacle_spacing),
+            );
+            if let Some(pos) = spot {
+                grid.insert(prev_positions.len(), pos, 0.);
+                prev_positions.push(pos);
+                obstacles.push(ObstacleSpec {
+                    pos: pos.into(),
+                    radius: OBSTACLE_RADIUS,
+                    motion: None,
+                    pulse: None,
+                });
+            }
+        }
+
+        // Enemy starting places.  Enemies get a wider berth from the player's start (so nobody is
+        // hit before they can react) but only need the usual room between themselves and
+        // obstacles.
+        let mut enemies = vec![];
+        for i in 0..config.enemy_count {
+            let spot = find_spot(
+                rng,
+                (&keep_clear, config.enemy_spacing),
+                (&prev_positions, &grid, config.obstacle_spacing),
+            );
+            if let Some(pos) = spot {
+                grid.insert(prev_positions.len(), pos, 0.);
+                prev_positions.push(pos);
+                let kind = match config.enemy_kinds.len() {
+                    0 => EnemyKind::default(),
+                    len => config.enemy_kinds[i % len],
+                };
+                // Patrol back and forth across the diagonal the player starts on
+                let waypoints = match kind {
+                    EnemyKind::Patroller => vec![pos.into(), [-pos[1], -pos[0]]],
+                    _ => vec![],
+                };
+                enemies.push(EnemySpec {
+                    kind,
+                    spawn: pos.into(),
+                    waypoints,
+                    navigation: config.enemy_navigation,
+                    ..EnemySpec::default()
+                });
+            }
+        }
+
+        Self {
+            name: format!("Seed {}", config.seed),
+            author: String::new(),
+            par_time: None,
+            player_start: player_start_pos.into(),
+            player_radius: PLAYER_RADIUS,
+            boundary: config.boundary,
+            goals: vec![GoalSpec {
+                pos: goal_pos.into(),
+                radius: GOAL_RADIUS,
+            }],
+            obstacles,
+            walls: vec![],
+            enemies,
+        }
+    }
+}
+
+/// Whether `pos` is closer to any of `others` than `spacing`, which is a *squared* distance.
+/// `grid` holds the index of each of `others`.
+fn too_close(pos: &Position, others: &[Position], grid: &SpatialHash, spacing: f32) -> bool {
+    grid.query(*pos, spacing.max(0.).sqrt())
+        .into_iter()
+        .any(|i| distance2(pos, &others[i]) < spacing)
+}
+
+/// Pick a random spot at least `keep_clear.1` from every point in `keep_clear.0`, and at least
+/// `placed.2` from everything already `placed` (positions, and a grid holding their indices).  Both
+/// spacings are *squared* distances.  `None` if the arena is too crowded to find one.
+fn find_spot(
+    rng: &mut ChaCha8Rng,
+    keep_clear: (&[Position], f32),
+    placed: (&[Position], &SpatialHash, f32),
+) -> Option<Position> {
+    (0..PLACEMENT_TRIES)
+        .map(|_| Position::new(rng.gen::<f32>() * 2.0 - 1.0, rng.gen::<f32>() * 2.0 - 1.0))
+        .find(|pos| {
+            keep_clear
+                .0
+                .iter()
+                .all(|other| distance2(pos, other) >= keep_clear.1)
+                && !too_close(pos, placed.0, placed.1, placed.2)
+        })
+}
@@ -0,0 +1,47 @@
+//! Circle Gauntlet simulation.  Everything needed to play the game lives here, without any window
+//! or audio device, so that gameplay can be driven (and tested) headless.  The `r_circlegauntlet`
+//! binary is a thin shell that feeds input into a `Game` and draws the resulting `World`.
+
+pub mod behavior;
+pub mod campaign;
+pub mod cli;
+pub mod collision;
+pub mod components;
+pub mod difficulty;
+pub mod game;
+pub mod ghost;
+pub mod hud;
+pub mod level;
+pub mod motion;
+pub mod navigation;
+pub mod physics;
+pub mod render;
+pub mod replay;
+pub mod score;
+pub mod session;
+pub mod solver;
+pub mod spatial;
+pub mod storage;
+pub mod timestep;
+
+pub use campaign::{Campaign, CampaignRun};
+pub use components::*;
+pub use difficulty::Difficulty;
+pub use game::{Game, GameConfig, GameEvent, Input};
+pub use level::Level;
+pub use physics::PhysicsConfig;
+pub use replay::Replay;
+pub use rusty_core::glm;
+pub use session::{Action, Session, State};
+pub use storage::FileError;
+pub use timestep::{FixedTimestep, TICK, TICK_RATE};
+
+pub const GOAL_RADIUS: f32 = 1. / 8.;
+pub const OBSTACLE_RADIUS: f32 = 1. / 12.;
+pub const PLAYER_RADIUS: f32 = 1. / 16.;
+pub const LIFE_MAX: i32 = 10;
+pub const LIFE_CIRCLE_RADIUS: f32 = 1. / 48.;
+pub const ENEMY_WIDTH: f32 = 1. / 8.;
+pub const WALL_THICKNESS: f32 = 1. / 32.;
+/// How close something has to come to the player, without touching, to count as a near miss
+pub const NEAR_MISS_MARGIN: f32 = 1. / 40.;
@@ -1,407 +1,759 @@
-use legion::prelude::*;
-use rand::prelude::*;
+use r_circlegauntlet::campaign::{Advance, Progress};
+use r_circlegauntlet::cli::{Command, Options, USAGE};
+use r_circlegauntlet::ghost::{BestRuns, Ghost};
+use r_circlegauntlet::hud::{self, Label};
+use r_circlegauntlet::physics::PhysicsWatcher;
+use r_circlegauntlet::render::{Renderer, Scene};
+use r_circlegauntlet::score::{self, HighScores, Score};
+use r_circlegauntlet::*;
 use rusty_engine::audio::Audio;
-use rusty_engine::gfx::event::{ButtonProcessor, GameEvent};
-use rusty_engine::gfx::{color::Color, Sprite, Window};
-use rusty_engine::glm::{distance, distance2, reflect_vec, Vec2};
+use rusty_engine::gfx::event::{
+    ButtonProcessor, ButtonState, ButtonValue, GameEvent as WindowEvent,
+};
+use rusty_engine::gfx::{color::Color, ShapeStyle, Sprite, Window};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process;
 use std::time::Instant;
 
-const GOAL_RADIUS: f32 = 1. / 8.;
-const OBSTACLE_RADIUS: f32 = 1. / 12.;
-const PLAYER_RADIUS: f32 = 1. / 16.;
-const LIFE_MAX: i32 = 10;
-const LIFE_CIRCLE_RADIUS: f32 = 1. / 48.;
-const ENEMY_WIDTH: f32 = 1. / 8.;
-
-type Position = Vec2;
-struct Velocity(Vec2);
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct Goal;
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct Obstacle;
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct Player;
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct SpriteIndex(usize);
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct Enemy;
+const CAMPAIGN_PATH: &str = "levels/campaign.toml";
+/// Where movement is tuned, unless `--physics` says otherwise
+const PHYSICS_PATH: &str = "physics.toml";
 
 fn main() {
-    let mut audio = Audio::new();
-    audio.add("bounce", "sound/bounce.wav");
-    audio.add("death", "sound/death.wav");
-    audio.add("startup", "sound/startup.wav");
-    audio.add("warning_one_life", "sound/warning_one_life.wav");
-    audio.add("win", "sound/win.wav");
-    audio.play("startup");
-
-    let universe = Universe::new();
-    let mut world = universe.create_world();
-    let mut window = Window::new(None, "Circle Gauntlet");
-
-    // (Sprites aren't Send)
-    let mut sprites = vec![
-        // Goal circle (large-ish, green)
-        Sprite::smooth_circle(
-            &window,
-            Position::new(0., 0.), // Ignored
-            0.,
-            1.,
-            GOAL_RADIUS,
-            Color::new(0., 1., 0.),
-        ),
-        // Player circle (small-ish, blue)
-        Sprite::smooth_circle(
-            &window,
-            Position::new(0., 0.), // Ignored
-            0.,
-            1.,
-            PLAYER_RADIUS,
-            Color::new(0., 0., 1.),
-        ),
-        // Obstacle circles -- reusing the same sprite for all instances is probably a terrible idea, but I want to try it
-        Sprite::smooth_circle(
-            &window,
-            Position::new(0., 0.), // Ignored
-            0.,
-            1.,
-            OBSTACLE_RADIUS,
-            Color::new(1., 0., 0.),
-        ),
-        // Life Circles - each circle represents a unit of life
-        Sprite::smooth_circle(
-            &window,
-            Position::new(0., 0.), // Ignored
-            0.,
-            1.,
-            LIFE_CIRCLE_RADIUS,
-            Color::new(0., 0., 1.),
-        ),
-        // Enemy - Square enemy chases the player
-        // Sprite::new_rectangle(
-        //     &window,
-        //     Position::new(0., 0.),
-        //     0.,
-        //     1.,
-        //     ENEMY_WIDTH,
-        //     ENEMY_WIDTH,
-        //     Color::new(1.0, 1.0, 0.0),
-        //     ShapeStyle::Fill,
-        // ),
-        Sprite::smooth_circle(
-            &window,
-            Position::new(0., 0.),
-            0.,
-            1.,
-            ENEMY_WIDTH * 0.5,
-            Color::new(1.0, 1.0, 0.0),
-        ),
-    ];
-
-    let goal_start_pos = Position::new(0.75, -0.75);
-    world.insert((Goal,), vec![(goal_start_pos, SpriteIndex(0))]);
-    let player_start_pos = Position::new(-0.75, 0.75);
-    world.insert(
-        (Player,),
-        vec![(
-            player_start_pos,
-            Velocity(Vec2::new(0.0, 0.0)),
-            SpriteIndex(1),
-        )],
-    );
-
-    // Obstacle starting places
-    let mut rng = rand::thread_rng();
-    let mut prev_positions = vec![];
-    let obstacle_spacing = 0.1;
-    for _ in 0..16 {
-        let mut pos = player_start_pos;
-        while distance2(&pos, &player_start_pos) < obstacle_spacing
-            || distance2(&pos, &goal_start_pos) < obstacle_spacing
-            || prev_positions
-                .iter()
-                .map(|ref x| distance2(&pos, &x))
-                .min_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-                .unwrap_or(500.)
-                .partial_cmp(&obstacle_spacing)
-                .unwrap_or(std::cmp::Ordering::Equal)
-                == std::cmp::Ordering::Less
-        {
-            pos = Position::new(rng.gen::<f32>() * 2.0 - 1.0, rng.gen::<f32>() * 2.0 - 1.0);
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
         }
-        prev_positions.push(pos);
-        world.insert((Obstacle,), vec![(pos, SpriteIndex(2))]);
-    }
-
-    // Enemy starting place
-    let enemy_spacing = 0.125;
-    let mut pos = player_start_pos;
-    while distance2(&pos, &player_start_pos) < enemy_spacing
-        || distance2(&pos, &goal_start_pos) < enemy_spacing
-        || prev_positions
-            .iter()
-            .map(|ref x| distance2(&pos, &x))
-            .min_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-            .unwrap_or(500.)
-            .partial_cmp(&enemy_spacing)
-            .unwrap_or(std::cmp::Ordering::Equal)
-            == std::cmp::Ordering::Less
-    {
-        pos = Position::new(rng.gen::<f32>() * 2.0 - 1.0, rng.gen::<f32>() * 2.0 - 1.0);
-    }
-    prev_positions.push(pos);
-    world.insert(
-        (Enemy,),
-        vec![(Position::new(0.75, 0.75), Velocity(Vec2::new(0.0, 0.0)))],
-    );
-
-    // GAME LOOP
-    let mut life = LIFE_MAX;
-    let mut button_processor = ButtonProcessor::new();
-    let mut instant = Instant::now();
-    'gameloop: loop {
-        let delta = instant.elapsed();
-        instant = Instant::now();
-        let mut dead = false;
-
-        // Process player input
-        for event in window.poll_game_events() {
-            match event {
-                GameEvent::Quit => break 'gameloop,
-                GameEvent::Button {
-                    button_value,
-                    button_state,
-                } => button_processor.process(button_value, button_state),
-                _ => {}
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return;
+    }
+    match &options.command {
+        Command::ValidateLevel(path) => {
+            validate_level(path);
+            return;
+        }
+        Command::Scores { export } => {
+            list_scores(export.as_deref());
+            return;
+        }
+        Command::Play => {}
+    }
+
+    // Without `--physics`, the bundled tuning file is optional
+    let physics_path = options
+        .physics
+        .clone()
+        .or_else(|| Some(Path::new(PHYSICS_PATH).to_owned()).filter(|path| path.exists()));
+    let physics = match &physics_path {
+        Some(path) => load_physics(path),
+        None => PhysicsConfig::default(),
+    };
+    let watcher = physics_path.map(PhysicsWatcher::new);
+
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let config = options.game_config(GameConfig {
+        seed,
+        physics,
+        ..GameConfig::default()
+    });
+
+    if let Some(path) = &options.replay {
+        let replay = match Replay::load(path) {
+            Ok(replay) => replay,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
             }
+        };
+        let title = format!("Circle Gauntlet - replay of {}", replay.level.name);
+        let mut frontend = Frontend::new(&title, physics, None);
+        frontend.watch(&replay);
+        frontend.audio.wait();
+        return;
+    }
+
+    if options.campaign {
+        let mut frontend = Frontend::new("Circle Gauntlet - Campaign", physics, watcher);
+        play_campaign(config, &mut frontend, options.record.as_deref());
+        frontend.audio.wait();
+        return;
+    }
+
+    let level = options.level.as_deref().map(load_level);
+    let title = match &level {
+        Some(level) => format!("Circle Gauntlet - {}", level.name),
+        None => format!("Circle Gauntlet - seed {}", seed),
+    };
+    let generated = level.is_none();
+    let mut session = Session::new(config, level);
+    if generated {
+        warn_if_crowded(session.game());
+    }
+    let mut frontend = Frontend::new(&title, physics, watcher);
+    frontend.run(&mut session, false);
+    println!("Seed: {}", session.game().seed());
+    if let Some(path) = &options.record {
+        save_replay(path, &session);
+    }
+    frontend.audio.wait();
+}
+
+/// Say so if a generated layout couldn't fit everything it was asked for
+fn warn_if_crowded(game: &Game) {
+    let (config, level) = (game.config(), game.level());
+    if level.obstacles.len() < config.obstacle_count {
+        eprintln!(
+            "warning: only {} of {} obstacles fit with a way through to the goal",
+            level.obstacles.len(),
+            config.obstacle_count
+        );
+    }
+    if level.enemies.len() < config.enemy_count {
+        eprintln!(
+            "warning: only {} of {} enemies fit",
+            level.enemies.len(),
+            config.enemy_count
+        );
+    }
+}
+
+fn load_level(path: &Path) -> Level {
+    match Level::load(path) {
+        Ok(level) => level,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
         }
+    }
+}
 
-        // Get the player's position
-        let mut player_pos = Position::new(0., 0.);
-        for pos in <Read<Position>>::query()
-            .filter(tag_value(&Player))
-            .iter(&mut world)
-        {
-            player_pos = *pos;
+fn load_physics(path: &Path) -> PhysicsConfig {
+    match PhysicsConfig::load(path) {
+        Ok(physics) => physics,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
         }
+    }
+}
 
-        // Detect Obstacle Collision
-        let mut maybe_collision = None;
-        for pos in <Read<Position>>::query()
-            .filter(tag_value(&Obstacle))
-            .iter(&mut world)
-        {
-            if distance(&player_pos, &*pos) < PLAYER_RADIUS + OBSTACLE_RADIUS {
-                maybe_collision = Some(*pos);
+/// Print every high score table, or write them all out as CSV to `export` (`-` for stdout)
+fn list_scores(export: Option<&Path>) {
+    let high_scores = HighScores::default_path()
+        .and_then(|path| HighScores::load(&path).ok())
+        .unwrap_or_default();
+    let result = match export {
+        Some(path) if path == Path::new("-") => high_scores.export_csv(&mut std::io::stdout()),
+        Some(path) => std::fs::File::create(path)
+            .and_then(|mut file| high_scores.export_csv(&mut file))
+            .map(|()| println!("Exported high scores to {}", path.display())),
+        None => {
+            if high_scores.levels.is_empty() {
+                println!("No high scores yet.  Win a level to get on the board!");
+            }
+            for (key, table) in &high_scores.levels {
+                println!("{} ({})", table.name, key);
+                print!("{}", score::format_table(&table.scores, None));
             }
+            Ok(())
+        }
+    };
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+/// Save a recording of the session's latest attempt
+fn save_replay(path: &Path, session: &Session) {
+    match session.replay().save(path) {
+        Ok(()) => println!("Replay saved to {}", path.display()),
+        Err(err) => eprintln!("couldn't save the replay to {}: {}", path.display(), err),
+    }
+}
+
+/// Put a win on the high score table for its layout, and print the table.  Returns the table and
+/// where the win placed in it, to show on the win screen.
+fn record_score(game: &Game) -> Option<(Vec<Score>, Option<usize>)> {
+    let path = HighScores::default_path()?;
+    let mut high_scores = HighScores::load(&path).unwrap_or_default();
+    let score = Score::of(game);
+    println!("Score: {}", score.points);
+    let rank = high_scores.add(game.level(), score);
+    if let Err(err) = high_scores.save(&path) {
+        eprintln!("couldn't save high scores to {}: {}", path.display(), err);
+    }
+    let table = high_scores.table(game.level());
+    print!("{}", score::format_table(table, rank));
+    Some((table.to_vec(), rank))
+}
+
+/// How a replay ended, for printing once it's been played back.  `ending` is the event that ended
+/// the game, if anything did.
+fn replay_outcome(ending: Option<GameEvent>, game: &Game, ticks: u32) -> String {
+    let seconds = ticks as f32 / TICK_RATE as f32;
+    match ending {
+        Some(GameEvent::Won) => format!(
+            "Replay over: won in {:.2}s with {} life left",
+            seconds,
+            game.life()
+        ),
+        Some(_) => format!("Replay over: died after {:.2}s", seconds),
+        None => format!("Replay over: stopped after {:.2}s", seconds),
+    }
+}
+
+/// Report whether a level file can be beaten, exiting with an error if it can't
+fn validate_level(path: &Path) {
+    let level = load_level(path);
+    let analysis = solver::analyze(&level);
+    let narrowest = match analysis.narrowest {
+        Some(width) if width >= solver::clearance_limit(&level) * 2. => {
+            format!("at least {:.3}", width)
         }
+        Some(width) => format!("{:.3}", width),
+        None => "none: the start or goal is outside the arena".into(),
+    };
+    println!("{}: {}", path.display(), level.name);
+    println!("  narrowest passage: {}", narrowest);
+    println!("  player width:      {:.3}", level.player_radius * 2.);
+    if analysis.solvable {
+        println!("  solvable");
+    } else {
+        println!("  NOT solvable: the player can't fit through to a goal");
+        process::exit(1);
+    }
+}
 
-        // Adjust player velocity
-        // Save player position for the enemy to see
-        let mut player_pos = Position::new(0.0, 0.0);
-        for (pos, mut vel) in <(Write<Position>, Write<Velocity>)>::query()
-            .filter(tag_value(&Player))
-            .iter_mut(&mut world)
-        {
-            player_pos = *pos;
-            // Player's new velocity based on previous velocity and current input
-            let max_vel = 0.5;
-            let win_vel = 0.9;
-            let bounce_vel = 0.75;
-            let input_scale = 1.;
-            let drag = 0.8;
-
-            // Apply drag first
-            (*vel).0 *= 1.0 - drag * delta.as_secs_f32();
-
-            // Then apply accelleration in the direction of the input
-            let magnitude_before = (*vel).0.magnitude();
-            (*vel).0 += button_processor.direction * input_scale * delta.as_secs_f32();
-
-            // If we're over max velocity, clamp velocity magnitude to the same as before input
-            // accelleration so input only affects direction.
-            if (*vel).0.magnitude() > max_vel && (*vel).0.magnitude() > magnitude_before {
-                (*vel).0 = (*vel).0.normalize() * magnitude_before;
+/// Play the bundled campaign from the furthest level unlocked, saving progress as levels are won
+fn play_campaign(config: GameConfig, frontend: &mut Frontend, record: Option<&Path>) {
+    let campaign = match Campaign::load(CAMPAIGN_PATH) {
+        Ok(campaign) => campaign,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    };
+    let progress_path = Progress::default_path();
+    let mut progress = progress_path
+        .as_ref()
+        .and_then(|path| Progress::load(path).ok())
+        .unwrap_or_default();
+    let start = progress.unlocked(&campaign);
+    let mut run = CampaignRun::new(campaign, config, start);
+    let mut first = true;
+    loop {
+        let mut session = match run.start_level() {
+            Ok(session) => session,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
             }
-
-            // Collision with obstacle?
-            if let Some(collision_pos) = maybe_collision {
-                // Colliding hurts
-                life -= 1;
-                if life <= 0 {
-                    dead = true;
+        };
+        println!(
+            "Level {}/{}: {}",
+            run.level_index() + 1,
+            run.campaign().levels.len(),
+            session.game().level().name
+        );
+        // Only the first level waits at the title screen
+        if !first {
+            session.start();
+        }
+        first = false;
+        let outcome = frontend.run(&mut session, true);
+        if let Some(path) = record {
+            save_replay(
+                &replay::numbered_path(path, run.level_index() + 1),
+                &session,
+            );
+        }
+        // Keep whatever difficulty was picked on the title screen for the rest of the run
+        run.set_config(session.config().clone());
+        match outcome {
+            Outcome::Won => match run.level_won(session.game().life()) {
+                Advance::NextLevel(next) => {
+                    progress.unlock(run.campaign(), next);
+                    if let Some(path) = &progress_path {
+                        if let Err(err) = progress.save(path) {
+                            eprintln!("couldn't save progress to {}: {}", path.display(), err);
+                        }
+                    }
                 }
-                // Colliding makes a sound of some type
-                if life == 1 {
-                    audio.play("warning_one_life");
-                } else {
-                    audio.play("bounce");
+                Advance::Finished => {
+                    println!("YOU BEAT {}!", run.campaign().name.to_uppercase());
+                    frontend.victory_screen(&run.campaign().name);
+                    return;
                 }
-                // Reflect velocity & boost it upon collision
-                let normal_vector = (collision_pos - *pos).normalize();
-                let surface_vector = Vec2::new(-normal_vector[1], normal_vector[0]);
-                let new_velocity =
-                    -reflect_vec(&((*vel).0), &surface_vector).normalize() * bounce_vel;
-                (*vel).0 = new_velocity;
-            }
+            },
+            Outcome::Quit => return,
+        }
+    }
+}
 
-            // Almost to the goal?
-            let goal_distance = distance(&*pos, &goal_start_pos);
-            if goal_distance < PLAYER_RADIUS + GOAL_RADIUS {
-                (*vel).0 += ((goal_start_pos - *pos).normalize() * delta.as_secs_f32()).normalize()
-                    * win_vel
-                    * delta.as_secs_f32();
-            }
+/// Width and height of the window in pixels
+const WINDOW_SIZE: u32 = 1024;
 
-            // Reached the goal?
-            if goal_distance < (PLAYER_RADIUS + GOAL_RADIUS) / 3. {
-                println!("YOU WIN!");
-                audio.play("win");
-                break 'gameloop;
-            }
+/// Sizes are rounded to this many steps per unit before looking up a sprite, so something that
+/// keeps changing size reuses a handful of sprites (scaled to fit) instead of making a new one
+/// every frame
+const RADIUS_STEPS: f32 = 256.;
+
+/// Sprites, made the first time each shape, size and color is needed
+#[derive(Default)]
+struct SpriteCache {
+    sprites: HashMap<(u32, [u32; 3]), Sprite>,
+    translucent: HashMap<(u32, [u32; 3], u32), Sprite>,
+    rectangles: HashMap<([u32; 2], [u32; 3]), Sprite>,
+}
+
+impl SpriteCache {
+    /// Draw a circle of `radius` centered on `pos`
+    fn draw(&mut self, window: &mut Window, pos: Position, radius: f32, tint: Tint) {
+        let steps = (radius * RADIUS_STEPS).round().max(1.);
+        let key = (steps as u32, tint.0.map(f32::to_bits));
+        let sprite = self.sprites.entry(key).or_insert_with(|| {
+            let [r, g, b] = tint.0;
+            Sprite::smooth_circle(
+                window,
+                Position::zeros(), // Ignored
+                0.,
+                1.,
+                steps / RADIUS_STEPS,
+                Color::new(r, g, b),
+            )
+        });
+        sprite.transform.pos = pos;
+        sprite.transform.scale = radius * RADIUS_STEPS / steps;
+        sprite.draw(window);
+    }
+
+    /// Draw a circle of `radius` centered on `pos` that only covers `opacity` of what's under it.
+    /// Shapes can't be see-through, but images can, so these are drawn from a PNG made to order.
+    fn draw_translucent(
+        &mut self,
+        window: &mut Window,
+        pos: Position,
+        radius: f32,
+        tint: Tint,
+        opacity: f32,
+    ) {
+        let steps = (radius * RADIUS_STEPS).round().max(1.);
+        let key = (steps as u32, tint.0.map(f32::to_bits), opacity.to_bits());
+        // Images are drawn at their size in pixels
+        let size = (steps / RADIUS_STEPS * WINDOW_SIZE as f32).ceil() as u32;
+        let count = self.translucent.len();
+        let sprite = self.translucent.entry(key).or_insert_with(|| {
+            let path = std::env::temp_dir()
+                .join(format!("r_circlegauntlet-{}", process::id()))
+                .join(format!("translucent-{}.png", count));
+            std::fs::create_dir_all(path.parent().unwrap())
+                .and_then(|()| std::fs::write(&path, circle_png(size, tint, opacity)))
+                .expect("couldn't write a sprite's image");
+            Sprite::new_image(
+                window,
+                Position::zeros(), // Ignored
+                0.,
+                1.,
+                None,
+                &path.to_string_lossy(),
+            )
+        });
+        sprite.transform.pos = pos;
+        sprite.transform.scale = radius * WINDOW_SIZE as f32 / size as f32;
+        sprite.draw(window);
+    }
+
+    /// Draw a rectangle centered on `pos`
+    fn draw_rectangle(
+        &mut self,
+        window: &mut Window,
+        pos: Position,
+        rectangle: Rectangle,
+        tint: Tint,
+    ) {
+        let size = rectangle.half_size * 2.;
+        let key = (
+            [size[0].to_bits(), size[1].to_bits()],
+            tint.0.map(f32::to_bits),
+        );
+        let sprite = self.rectangles.entry(key).or_insert_with(|| {
+            let [r, g, b] = tint.0;
+            Sprite::new_rectangle(
+                window,
+                Position::zeros(), // Ignored
+                0.,
+                1.,
+                size[0],
+                size[1],
+                Color::new(r, g, b),
+                ShapeStyle::Fill,
+            )
+        });
+        sprite.transform.pos = pos;
+        sprite.transform.direction = rectangle.angle;
+        sprite.draw(window);
+    }
+}
+
+/// A `size` pixel square PNG with a circle of `tint` filling it, `opacity` of the way opaque, and
+/// nothing at all around it
+fn circle_png(size: u32, tint: Tint, opacity: f32) -> Vec<u8> {
+    let [r, g, b] = tint
+        .0
+        .map(|channel| (channel.clamp(0., 1.) * 255.).round() as u8);
+    let alpha = (opacity.clamp(0., 1.) * 255.).round() as u8;
+    let radius = size as f32 / 2.;
+    let mut rgba = Vec::with_capacity(size as usize * size as usize * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let offset = glm::Vec2::new(x as f32 + 0.5 - radius, y as f32 + 0.5 - radius);
+            let inside = offset.magnitude() <= radius;
+            rgba.extend_from_slice(&[r, g, b, if inside { alpha } else { 0 }]);
         }
+    }
+    let mut png = vec![];
+    let mut encoder = png::Encoder::new(&mut png, size, size);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&rgba))
+        .expect("writing a PNG to memory can't fail");
+    png
+}
 
-        // Adjust enemy velocity
-        let mut enemy_bounce_normal_vector = Vec2::zeros();
-        let mut enemy_bounce = false;
-        for (mut pos, mut vel) in <(Write<Position>, Write<Velocity>)>::query()
-            .filter(tag_value(&Enemy))
-            .iter_mut(&mut world)
-        {
-            // Enemy's new velocity based on previous velocity and current input
-            let max_vel = 0.5 * 0.5;
-            let drag = 0.8;
-
-            // Apply drag first
-            (*vel).0 *= 1.0 - drag * delta.as_secs_f32();
-
-            // Then apply acceleration in the direction of the player
-            let magnitude_before = (*vel).0.magnitude();
-            (*vel).0 += (player_pos - *pos) * delta.as_secs_f32();
-
-            // If we're over max velocity, clamp velocity magnitude to the same as before input
-            // acceleration so input only affects direction.
-            if (*vel).0.magnitude() > max_vel && (*vel).0.magnitude() > magnitude_before {
-                (*vel).0 = (*vel).0.normalize() * magnitude_before;
-            }
+/// How a call to `Frontend::run` ended
+enum Outcome {
+    Won,
+    Quit,
+}
+
+/// Everything that talks to the player: the window (with its sprites and input) and the audio
+struct Frontend {
+    window: Window,
+    // (Sprites aren't Send)
+    sprites: SpriteCache,
+    audio: Audio,
+    button_processor: ButtonProcessor,
+    /// How things move right now.  Edits made while playing carry over to every later session.
+    physics: PhysicsConfig,
+    /// Reloads `physics` when its file changes
+    watcher: Option<PhysicsWatcher>,
+    /// Where the best run on each level is kept, if there's anywhere to keep it
+    best_runs: Option<BestRuns>,
+    /// The best run on the level being played, to race against
+    ghost: Option<Ghost>,
+    /// The high score table for the last win, and where it placed
+    standing: Option<(Vec<Score>, Option<usize>)>,
+}
 
-            // Update position
-            let new_pos = *pos + (*vel).0 * delta.as_secs_f32();
-            *pos = new_pos;
+impl Frontend {
+    fn new(title: &str, physics: PhysicsConfig, watcher: Option<PhysicsWatcher>) -> Self {
+        let mut audio = Audio::new();
+        audio.add("bounce", "sound/bounce.wav");
+        audio.add("death", "sound/death.wav");
+        audio.add("startup", "sound/startup.wav");
+        audio.add("warning_one_life", "sound/warning_one_life.wav");
+        audio.add("win", "sound/win.wav");
+        audio.play("startup");
+
+        let window = Window::new(Some(WINDOW_SIZE), title);
+        Self {
+            window,
+            sprites: SpriteCache::default(),
+            audio,
+            button_processor: ButtonProcessor::new(),
+            physics,
+            watcher,
+            best_runs: BestRuns::default_dir().map(BestRuns::new),
+            ghost: None,
+            standing: None,
+        }
+    }
 
-            // Kill player?
-            if distance(&player_pos, &pos) < PLAYER_RADIUS + (ENEMY_WIDTH * 0.5) {
-                life -= 1;
-                if life <= 0 {
-                    dead = true;
+    /// Run the game loop until the player quits, or if `stop_on_win` is set, until they win and
+    /// press a key to move on from the win screen
+    fn run(&mut self, session: &mut Session, stop_on_win: bool) -> Outcome {
+        session.set_physics(self.physics);
+        self.ghost = self
+            .best_runs
+            .as_ref()
+            .and_then(|best_runs| best_runs.best(session.game().level()))
+            .map(|replay| Ghost::from_replay(&replay));
+        let mut timestep = FixedTimestep::default();
+        let mut instant = Instant::now();
+        loop {
+            let delta = instant.elapsed();
+            instant = Instant::now();
+
+            // Pick up edits to the physics file.  A bad edit keeps whatever was working before.
+            if let Some(watcher) = &mut self.watcher {
+                match watcher.poll() {
+                    Some(Ok(physics)) => {
+                        println!("Reloaded {}", watcher.path().display());
+                        self.physics = physics;
+                        session.set_physics(physics);
+                    }
+                    Some(Err(err)) => eprintln!("error: {} (keeping the old physics)", err),
+                    None => {}
                 }
+            }
+
+            // Process player input
+            for event in self.window.poll_game_events() {
+                match event {
+                    WindowEvent::Quit => return Outcome::Quit,
+                    WindowEvent::Button {
+                        button_value,
+                        button_state,
+                    } => {
+                        self.button_processor.process(button_value, button_state);
+                        if button_state != ButtonState::Pressed {
+                            continue;
+                        }
+                        if stop_on_win && session.state() == State::Won {
+                            return Outcome::Won;
+                        }
+                        let action = match button_value {
+                            ButtonValue::Action1 => Action::Pause,
+                            ButtonValue::Action2 => Action::Retry,
+                            ButtonValue::Action3 => Action::NewLayout,
+                            ButtonValue::Increase => Action::Harder,
+                            ButtonValue::Decrease => Action::Easier,
+                            _ => continue,
+                        };
+                        session.handle(action);
+                    }
+                    _ => {}
+                }
+            }
+
+            // Advance the simulation in fixed-size ticks.  Time doesn't pass unless we're playing.
+            if session.state() != State::Playing {
+                timestep = FixedTimestep::default();
+            }
+            let input = Input {
+                direction: self.button_processor.direction,
+            };
+            let mut events = vec![];
+            for _ in 0..timestep.advance(delta.as_secs_f32()) {
+                events.extend(session.step(&input, timestep.tick()));
+            }
+            let won = self.play_sounds(&events);
+            if won {
+                self.race_finished(session);
+                self.standing = record_score(session.game());
+            }
 
-                // // Colliding makes a sound of some type
-                if life == 1 {
-                    audio.play("warning_one_life");
+            // Moving things are drawn part of the way between where they were last tick and where
+            // they are now, so motion looks smooth even though the simulation runs at its own rate
+            let labels = if session.state() == State::Won {
+                let subtitle = if stop_on_win {
+                    hud::CARRY_ON
                 } else {
-                    audio.play("bounce");
+                    hud::PLAY_AGAIN
+                };
+                let standing = self.standing.as_ref();
+                let standing = standing.map(|(table, rank)| (&table[..], *rank));
+                hud::win_screen(session.game(), subtitle, standing)
+            } else {
+                hud::labels(session.game(), session.state())
+            };
+            self.draw(
+                session.game(),
+                &labels,
+                session.replay().ticks(),
+                timestep.alpha(),
+            );
+        }
+    }
+
+    /// Compare a winning run against the best so far, and keep it if it's better
+    fn race_finished(&mut self, session: &Session) {
+        let replay = session.replay();
+        let seconds = replay.ticks() as f32 / TICK_RATE as f32;
+        match &self.ghost {
+            Some(ghost) => {
+                let split = (replay.ticks() as f32 - ghost.ticks() as f32) / TICK_RATE as f32;
+                if split < 0. {
+                    println!("{:.2}s: a new best, by {:.2}s!", seconds, -split);
+                } else {
+                    println!("{:.2}s: {:.2}s behind your best", seconds, split);
                 }
-                // Reflect velocity & boost it upon collision
-                enemy_bounce_normal_vector = (player_pos - *pos).normalize();
-                enemy_bounce = true;
-                vel.0 *= -0.5;
             }
+            None => println!("{:.2}s: your first win here", seconds),
+        }
+        let best_runs = match &self.best_runs {
+            Some(best_runs) => best_runs,
+            None => return,
+        };
+        match best_runs.offer(replay) {
+            Ok(true) => self.ghost = Some(Ghost::from_replay(replay)),
+            Ok(false) => {}
+            Err(err) => eprintln!(
+                "couldn't save your best run to {}: {}",
+                best_runs.dir().display(),
+                err
+            ),
         }
+    }
 
-        // Bounce the player off of the enemy
-        for (mut pos, mut vel) in <(Write<Position>, Write<Velocity>)>::query()
-            .filter(tag_value(&Player))
-            .iter_mut(&mut world)
-        {
-            let bounce_vel = 0.75;
-
-            if enemy_bounce {
-                let surface_vector = Vec2::new(
-                    -enemy_bounce_normal_vector[1],
-                    enemy_bounce_normal_vector[0],
-                );
-                let new_velocity =
-                    -reflect_vec(&((*vel).0), &surface_vector).normalize() * bounce_vel;
-                (*vel).0 = new_velocity;
+    /// Play back a recorded run until the player closes the window
+    fn watch(&mut self, replay: &Replay) {
+        self.ghost = None;
+        let mut game = replay.game();
+        let mut playback = replay.playback();
+        let mut timestep = FixedTimestep::default();
+        let mut instant = Instant::now();
+        let mut ending = None;
+        let mut reported = false;
+        loop {
+            let delta = instant.elapsed();
+            instant = Instant::now();
+
+            for event in self.window.poll_game_events() {
+                if let WindowEvent::Quit = event {
+                    return;
+                }
             }
 
-            // Update position
-            let new_pos = *pos + (*vel).0 * delta.as_secs_f32();
-            *pos = new_pos;
-
-            // Death by edge?
-            if new_pos[0] < -1. - PLAYER_RADIUS
-                || new_pos[0] > 1. + PLAYER_RADIUS
-                || new_pos[1] < -1. - PLAYER_RADIUS
-                || new_pos[1] > 1. + PLAYER_RADIUS
-            {
-                dead = true;
+            let mut events = vec![];
+            for _ in 0..timestep.advance(delta.as_secs_f32()) {
+                events.extend(playback.step(&mut game).unwrap_or_default());
+            }
+            self.play_sounds(&events);
+            ending = ending.or_else(|| {
+                events
+                    .iter()
+                    .copied()
+                    .find(|event| matches!(event, GameEvent::Won | GameEvent::Died))
+            });
+            let mut labels = hud::labels(&game, State::Playing);
+            if playback.is_finished() {
+                let outcome = replay_outcome(ending, &game, playback.tick());
+                if !reported {
+                    reported = true;
+                    println!("{}", outcome);
+                }
+                labels.extend(hud::banner("REPLAY OVER", &outcome));
             }
-        }
 
-        // RENDER THE SCENE
-        window.drawstart();
-
-        // Draw the Goal
-        for (pos, sprite_idx) in <(Read<Position>, Read<SpriteIndex>)>::query()
-            .filter(tag_value(&Goal))
-            .iter(&mut world)
-        {
-            let sprite = sprites.get_mut(sprite_idx.0).unwrap();
-            sprite.transform.pos = *pos;
-            sprite.draw(&mut window);
+            self.draw(&game, &labels, playback.tick(), timestep.alpha());
         }
+    }
 
-        // Draw the Obstacles
-        for (pos, sprite_idx) in <(Read<Position>, Read<SpriteIndex>)>::query()
-            .filter(tag_value(&Obstacle))
-            .iter(&mut world)
-        {
-            let sprite = sprites.get_mut(sprite_idx.0).unwrap();
-            sprite.transform.pos = *pos;
-            sprite.draw(&mut window);
+    /// Make the noise for everything that happened.  Returns whether the player won.
+    fn play_sounds(&mut self, events: &[GameEvent]) -> bool {
+        let mut won = false;
+        for event in events {
+            match event {
+                // Colliding makes a sound of some type
+                GameEvent::Hit { life } => {
+                    if *life == 1 {
+                        self.audio.play("warning_one_life");
+                    } else {
+                        self.audio.play("bounce");
+                    }
+                }
+                GameEvent::Won => {
+                    self.audio.play("win");
+                    won = true;
+                }
+                GameEvent::Died => self.audio.play("death"),
+            }
         }
+        won
+    }
 
-        // Draw the Player
-        for (pos, sprite_idx) in <(Read<Position>, Read<SpriteIndex>)>::query()
-            .filter(tag_value(&Player))
-            .iter(&mut world)
-        {
-            let sprite = sprites.get_mut(sprite_idx.0).unwrap();
-            sprite.transform.pos = *pos;
-            sprite.draw(&mut window);
-        }
+    /// RENDER THE SCENE, `tick` ticks into the current attempt, with `labels` written over it
+    fn draw(&mut self, game: &Game, labels: &[Label], tick: u32, alpha: f32) {
+        let scene = Scene {
+            game,
+            labels,
+            ghost: self.ghost.as_ref(),
+            tick,
+            alpha,
+        };
+        scene.draw(&mut Screen {
+            window: &mut self.window,
+            sprites: &mut self.sprites,
+        });
+    }
 
-        // Draw the life circles
-        for i in 0..life {
-            let pos = Position::new(
-                -1.0 + LIFE_CIRCLE_RADIUS + (2.0 * i as f32 * LIFE_CIRCLE_RADIUS),
-                1.0 - LIFE_CIRCLE_RADIUS,
-            );
-            let sprite = sprites.get_mut(3).unwrap();
-            sprite.transform.pos = pos;
-            sprite.draw(&mut window);
-        }
+    /// Celebrate beating the campaign called `name` until the player presses something
+    fn victory_screen(&mut self, name: &str) {
+        let labels = hud::banner(&format!("YOU BEAT {}!", name), hud::CARRY_ON);
+        let start = Instant::now();
+        loop {
+            for event in self.window.poll_game_events() {
+                match event {
+                    WindowEvent::Quit => return,
+                    WindowEvent::Button {
+                        button_value,
+                        button_state,
+                    } => {
+                        self.button_processor.process(button_value, button_state);
+                        if button_state == ButtonState::Pressed {
+                            return;
+                        }
+                    }
+                    _ => {}
+                }
+            }
 
-        // Draw the enemy
-        for (pos,) in <(Read<Position>,)>::query()
-            .filter(tag_value(&Enemy))
-            .iter(&mut world)
-        {
-            let sprite = sprites.get_mut(4).unwrap();
-            sprite.transform.pos = *pos;
-            sprite.draw(&mut window);
+            // A ring of goals spinning around the player
+            let t = start.elapsed().as_secs_f32();
+            let mut screen = Screen {
+                window: &mut self.window,
+                sprites: &mut self.sprites,
+            };
+            screen.start();
+            for i in 0..8 {
+                let angle = t + i as f32 * std::f32::consts::TAU / 8.;
+                let pos = Position::new(angle.cos(), angle.sin()) * 0.6;
+                screen.circle(pos, GOAL_RADIUS, Tint::GOAL);
+            }
+            screen.circle(Position::zeros(), PLAYER_RADIUS, Tint::PLAYER);
+            for label in &labels {
+                screen.label(label);
+            }
+            screen.finish();
         }
+    }
+}
 
-        window.drawfinish();
+/// The window, drawn on with sprites
+struct Screen<'a> {
+    window: &'a mut Window,
+    sprites: &'a mut SpriteCache,
+}
 
-        if dead {
-            println!("YOU DIED!");
-            audio.play("death");
-            break 'gameloop;
-        }
+impl Renderer for Screen<'_> {
+    fn start(&mut self) {
+        self.window.drawstart();
+    }
+
+    fn finish(&mut self) {
+        self.window.drawfinish();
+    }
+
+    fn circle(&mut self, pos: Position, radius: f32, tint: Tint) {
+        self.sprites.draw(self.window, pos, radius, tint);
+    }
+
+    fn translucent_circle(&mut self, pos: Position, radius: f32, tint: Tint, opacity: f32) {
+        self.sprites
+            .draw_translucent(self.window, pos, radius, tint, opacity);
+    }
+
+    fn rectangle(&mut self, pos: Position, rectangle: Rectangle, tint: Tint) {
+        self.sprites
+            .draw_rectangle(self.window, pos, rectangle, tint);
     }
-    audio.wait();
 }
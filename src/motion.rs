@@ -0,0 +1,84 @@
+//! Obstacles that move and change size.
+//!
+//! Ping-pong, orbit and pulse are all worked out from how long the game has been running, so they
+//! never drift no matter how many steps it took to get there.  Bouncing around the arena has to be
+//! stepped along, so it keeps its speed in the obstacle's `Velocity`.
+
+use crate::level::{Motion, ObstacleSpec, Pulse};
+use rusty_core::glm::Vec2;
+use std::f32::consts::TAU;
+
+/// Component for an obstacle that moves or pulses
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Animation {
+    motion: Option<Motion>,
+    pulse: Option<Pulse>,
+    /// Where the obstacle started
+    origin: Vec2,
+    /// The size it started at
+    radius: f32,
+}
+
+impl Animation {
+    /// The animation an obstacle from a level asks for, or `None` if it just sits there
+    pub fn from_spec(spec: &ObstacleSpec) -> Option<Self> {
+        if spec.motion.is_none() && spec.pulse.is_none() {
+            return None;
+        }
+        Some(Self {
+            motion: spec.motion,
+            pulse: spec.pulse,
+            origin: Vec2::from(spec.pos),
+            radius: spec.radius,
+        })
+    }
+
+    /// The velocity to start with
+    pub fn initial_velocity(&self) -> Vec2 {
+        match self.motion {
+            Some(Motion::Bounce { velocity }) => Vec2::from(velocity),
+            _ => Vec2::zeros(),
+        }
+    }
+
+    /// Move the obstacle from `time - dt` to `time` seconds into the game.  `vel` ends up as how
+    /// fast it moved during the step.
+    pub fn update(&self, time: f32, dt: f32, pos: &mut Vec2, vel: &mut Vec2, radius: &mut f32) {
+        if let Some(pulse) = self.pulse {
+            // Start at the usual size and ease out to the pulse size and back
+            let phase = (1. - (time * TAU / pulse.period).cos()) / 2.;
+            *radius = self.radius + (pulse.radius - self.radius) * phase;
+        }
+        let before = *pos;
+        match self.motion {
+            Some(Motion::PingPong { to, period }) => {
+                let phase = (time / period).fract();
+                let along = 1. - (2. * phase - 1.).abs();
+                *pos = self.origin + (Vec2::from(to) - self.origin) * along;
+            }
+            Some(Motion::Orbit { center, period }) => {
+                let center = Vec2::from(center);
+                let offset = self.origin - center;
+                let angle = offset[1].atan2(offset[0]) + time * TAU / period;
+                *pos = center + Vec2::new(angle.cos(), angle.sin()) * offset.magnitude();
+            }
+            Some(Motion::Bounce { .. }) => {
+                *pos += *vel * dt;
+                for axis in 0..2 {
+                    let limit = 1. - *radius;
+                    if (pos[axis] > limit && vel[axis] > 0.)
+                        || (pos[axis] < -limit && vel[axis] < 0.)
+                    {
+                        vel[axis] = -vel[axis];
+                        pos[axis] = pos[axis].clamp(-limit, limit);
+                    }
+                }
+                return;
+            }
+            None => {}
+        }
+        if dt > 0. {
+            *vel = (*pos - before) / dt;
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Finding a way around obstacles for enemies that are smart enough to look for one.
+//!
+//! The arena is divided into a grid, and every cell an enemy's center can't be in without touching
+//! an obstacle is blocked.  A* over that grid gives the route; the enemy just heads for a point a
+//! few cells along it, which is plenty to steer it around whatever is in the way.
+
+use crate::collision::Body;
+use rusty_core::glm::Vec2;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Cells along each side of the grid
+const GRID_SIZE: usize = 64;
+/// How many cells along the route to aim for.  Any closer and enemies hug corners; any further and
+/// they cut them.
+const LOOKAHEAD: usize = 3;
+
+/// Which cells of the arena are blocked by obstacles
+#[derive(Clone, Debug)]
+pub struct NavGrid {
+    blocked: Vec<bool>,
+}
+
+impl NavGrid {
+    /// A grid for something with radius `clearance` to find its way around `obstacles` (position,
+    /// radius)
+    pub fn new(obstacles: &[(Vec2, f32)], clearance: f32) -> Self {
+        let bodies: Vec<Body> = obstacles
+            .iter()
+            .map(|&(pos, radius)| Body::fixed(pos, radius))
+            .collect();
+        Self::from_bodies(&bodies, clearance)
+    }
+
+    /// A grid for something with radius `clearance` to find its way around `obstacles` of any
+    /// shape, where they are at the start of the step
+    pub fn from_bodies(obstacles: &[Body], clearance: f32) -> Self {
+        let mut blocked = vec![false; GRID_SIZE * GRID_SIZE];
+        for obstacle in obstacles {
+            let reach = Vec2::repeat(obstacle.shape.bounding_radius() + clearance);
+            let (min_x, min_y) = cell_coords(obstacle.pos - reach);
+            let (max_x, max_y) = cell_coords(obstacle.pos + reach);
+            for y in min_y..=max_y {
+                for x in min_x..=max_x {
+                    if obstacle.shape.distance(obstacle.pos, cell_center(x, y)) < clearance {
+                        blocked[y * GRID_SIZE + x] = true;
+                    }
+                }
+            }
+        }
+        Self { blocked }
+    }
+
+    /// Whether the cell containing `pos` is blocked
+    pub fn is_blocked(&self, pos: Vec2) -> bool {
+        let (x, y) = cell_coords(pos);
+        self.blocked[y * GRID_SIZE + x]
+    }
+
+    /// The point to head for to get from `from` to `to` without running into anything.  Straight
+    /// at `to` if nothing is in the way, or if there's no way around.
+    pub fn steer(&self, from: Vec2, to: Vec2) -> Vec2 {
+        match self.route(from, to) {
+            Some(route) if route.len() > LOOKAHEAD => {
+                let (x, y) = route[LOOKAHEAD];
+                cell_center(x, y)
+            }
+            _ => to,
+        }
+    }
+
+    /// Cells from the one containing `from` to the one containing `to`, both included.  The ends
+    /// are allowed to be blocked (whoever is there is already touching an obstacle), but nothing in
+    /// between is.
+    pub fn route(&self, from: Vec2, to: Vec2) -> Option<Vec<(usize, usize)>> {
+        let start = cell_coords(from);
+        let goal = cell_coords(to);
+        let index = |(x, y): (usize, usize)| y * GRID_SIZE + x;
+        let heuristic = |(x, y): (usize, usize)| {
+            let dx = (x as f32 - goal.0 as f32).abs();
+            let dy = (y as f32 - goal.1 as f32).abs();
+            dx.max(dy) + (std::f32::consts::SQRT_2 - 1.) * dx.min(dy)
+        };
+
+        let mut cost = vec![f32::INFINITY; GRID_SIZE * GRID_SIZE];
+        let mut came_from = vec![usize::MAX; GRID_SIZE * GRID_SIZE];
+        let mut open = BinaryHeap::new();
+        cost[index(start)] = 0.;
+        open.push(Candidate {
+            estimate: heuristic(start),
+            cell: start,
+        });
+        while let Some(Candidate { cell, estimate }) = open.pop() {
+            if cell == goal {
+                let mut route = vec![goal];
+                let mut i = index(goal);
+                while i != index(start) {
+                    i = came_from[i];
+                    route.push((i % GRID_SIZE, i / GRID_SIZE));
+                }
+                route.reverse();
+                return Some(route);
+            }
+            // Already found a better way here
+            if estimate > cost[index(cell)] + heuristic(cell) {
+                continue;
+            }
+            for (next, step) in self.neighbors(cell, goal) {
+                let next_cost = cost[index(cell)] + step;
+                if next_cost < cost[index(next)] {
+                    cost[index(next)] = next_cost;
+                    came_from[index(next)] = index(cell);
+                    open.push(Candidate {
+                        estimate: next_cost + heuristic(next),
+                        cell: next,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Open cells next to `cell` (including diagonally, as long as that doesn't cut a blocked
+    /// corner) and the cost of stepping to each.  `goal` counts as open even if it isn't.
+    fn neighbors(
+        &self,
+        (x, y): (usize, usize),
+        goal: (usize, usize),
+    ) -> impl Iterator<Item = ((usize, usize), f32)> + '_ {
+        let open = move |x: isize, y: isize| {
+            let in_grid =
+                (0..GRID_SIZE as isize).contains(&x) && (0..GRID_SIZE as isize).contains(&y);
+            let cell = (x as usize, y as usize);
+            in_grid && (cell == goal || !self.blocked[cell.1 * GRID_SIZE + cell.0])
+        };
+        let (x, y) = (x as isize, y as isize);
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+            .filter(move |&(dx, dy)| {
+                open(x + dx, y + dy) && (dx == 0 || dy == 0 || (open(x + dx, y) && open(x, y + dy)))
+            })
+            .map(move |(dx, dy)| {
+                let step = if dx == 0 || dy == 0 {
+                    1.
+                } else {
+                    std::f32::consts::SQRT_2
+                };
+                (((x + dx) as usize, (y + dy) as usize), step)
+            })
+    }
+}
+
+/// A cell waiting to be explored, ordered so the `BinaryHeap` pops the most promising first
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Candidate {
+    estimate: f32,
+    cell: (usize, usize),
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn cell_size() -> f32 {
+    2. / GRID_SIZE as f32
+}
+
+fn cell_center(x: usize, y: usize) -> Vec2 {
+    Vec2::new(
+        -1. + (x as f32 + 0.5) * cell_size(),
+        -1. + (y as f32 + 0.5) * cell_size(),
+    )
+}
+
+/// Column and row of the cell containing `pos`, clamped to the grid
+fn cell_coords(pos: Vec2) -> (usize, usize) {
+    let coord = |v: f32| (((v + 1.) / cell_size()).floor().max(0.) as usize).min(GRID_SIZE - 1);
+    (coord(pos[0]), coord(pos[1]))
+}
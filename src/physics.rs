@@ -0,0 +1,167 @@
+//! Tuning for how things move, loaded from a TOML file so it can be tweaked without recompiling.
+//!
+//! Every value is optional and falls back to the built-in default:
+//!
+//! ```toml
+//! max_speed = 0.5
+//! acceleration = 1.0
+//! drag = 0.8
+//! goal_pull = 0.9
+//! restitution = 0.75
+//! enemy_max_speed = 0.25
+//! enemy_drag = 0.8
+//! enemy_recoil = 0.5
+//! ```
+//!
+//! `PhysicsWatcher` notices when the file changes, so edits can be picked up while playing.
+
+use crate::storage::{self, FileError};
+use crate::timestep::{TICK, TICK_RATE};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// How the player and enemies move
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PhysicsConfig {
+    /// Input can't push the player faster than this
+    pub max_speed: f32,
+    /// How hard input pushes the player
+    pub acceleration: f32,
+    /// Fraction of the player's speed lost per second
+    pub drag: f32,
+    /// How hard the goal pulls the player in once they're touching it
+    pub goal_pull: f32,
+    /// How bouncy obstacles and enemies are: the fraction of the player's speed into a surface that
+    /// comes back out of it
+    pub restitution: f32,
+    /// Chasing can't push an enemy faster than this
+    pub enemy_max_speed: f32,
+    /// Fraction of an enemy's speed lost per second
+    pub enemy_drag: f32,
+    /// Fraction of its speed an enemy bounces back with after hitting the player
+    pub enemy_recoil: f32,
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            max_speed: 0.5,
+            acceleration: 1.,
+            drag: 0.8,
+            goal_pull: 0.9,
+            restitution: 0.75,
+            enemy_max_speed: 0.25,
+            enemy_drag: 0.8,
+            enemy_recoil: 0.5,
+        }
+    }
+}
+
+impl PhysicsConfig {
+    /// Load and validate a physics file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FileError> {
+        storage::load_file(path.as_ref(), Self::parse)
+    }
+
+    /// Parse and validate the contents of a physics file
+    pub fn parse(text: &str) -> Result<Self, FileError> {
+        let physics: PhysicsConfig = toml::from_str(text).map_err(|err| FileError::Parse {
+            path: PathBuf::new(),
+            kind: "physics",
+            message: err.to_string().trim_end().to_string(),
+        })?;
+        physics.validate()?;
+        Ok(physics)
+    }
+
+    /// Check the values that TOML can't check for us
+    pub fn validate(&self) -> Result<(), FileError> {
+        let invalid = |field: &str, message: &str| FileError::Invalid {
+            path: PathBuf::new(),
+            field: field.to_string(),
+            message: message.to_string(),
+        };
+        let fields = [
+            ("max_speed", self.max_speed),
+            ("acceleration", self.acceleration),
+            ("drag", self.drag),
+            ("goal_pull", self.goal_pull),
+            ("restitution", self.restitution),
+            ("enemy_max_speed", self.enemy_max_speed),
+            ("enemy_drag", self.enemy_drag),
+            ("enemy_recoil", self.enemy_recoil),
+        ];
+        for &(field, value) in &fields {
+            if !value.is_finite() || value < 0. {
+                return Err(invalid(field, "must be zero or more"));
+            }
+        }
+        for &(field, value) in &[
+            ("max_speed", self.max_speed),
+            ("enemy_max_speed", self.enemy_max_speed),
+        ] {
+            if value == 0. {
+                return Err(invalid(field, "must be positive"));
+            }
+        }
+        // Drag is applied once a tick as `speed *= 1 - drag * TICK`, so any more than this would
+        // flip things' direction every tick instead of slowing them down
+        for &(field, value) in &[("drag", self.drag), ("enemy_drag", self.enemy_drag)] {
+            if value * TICK >= 1. {
+                return Err(invalid(
+                    field,
+                    &format!(
+                        "must be less than {}, or things would turn around every tick",
+                        TICK_RATE
+                    ),
+                ));
+            }
+        }
+        if self.restitution > 1. {
+            return Err(invalid(
+                "restitution",
+                "must be at most 1.0, or bounces would speed things up",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Keeps an eye on a physics file, reloading it whenever it changes
+#[derive(Clone, Debug)]
+pub struct PhysicsWatcher {
+    path: PathBuf,
+    /// When the file was last changed, as of the last look
+    modified: Option<SystemTime>,
+}
+
+impl PhysicsWatcher {
+    /// Watch `path` for changes from now on.  Whatever is there now doesn't count as a change.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let modified = modified(&path);
+        Self { path, modified }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The newly loaded config if the file has changed since the last look, or why it couldn't be
+    /// loaded.  `None` if it hasn't changed.
+    pub fn poll(&mut self) -> Option<Result<PhysicsConfig, FileError>> {
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(PhysicsConfig::load(&self.path))
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
@@ -0,0 +1,427 @@
+//! Drawing the game, apart from whatever ends up showing it.  A `Scene` says what's in a frame and
+//! draws it through a `Renderer`, which only has to know how to fill circles and rectangles.  The
+//! binary's window is one `Renderer`; `Canvas` (pixels, saved as PNG) and `Ascii` (a grid of
+//! characters) are two more that need no GPU, so snapshots of levels can be compared in tests.
+//!
+//! Both of those sample one point in the middle of each pixel or character cell, with no
+//! smoothing, so the same scene always comes out exactly the same.
+
+use crate::collision::Shape;
+use crate::components::{
+    Enemy, Goal, Obstacle, Player, Position, PrevPosition, Radius, Rectangle, Tint,
+};
+use crate::game::Game;
+use crate::ghost::Ghost;
+use crate::hud::{self, Label};
+use crate::LIFE_CIRCLE_RADIUS;
+use legion::prelude::*;
+use rusty_core::glm::{self, Vec2};
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// How many times per second the player flips between visible and invisible while invulnerable
+pub const BLINK_RATE: f32 = 10.;
+/// The split-time bar is full length at this many seconds ahead or behind
+pub const SPLIT_RANGE: f32 = 5.;
+/// How much of the player's color the ghost of a best run covers what's under it with
+pub const GHOST_OPACITY: f32 = 0.4;
+
+/// Somewhere to draw.  Positions and sizes are in arena coordinates (-1.0 to 1.0, y up).
+pub trait Renderer {
+    /// Clear everything drawn for the last frame
+    fn start(&mut self) {}
+
+    /// Show the frame drawn since `start`
+    fn finish(&mut self) {}
+
+    /// Fill a circle of `radius` centered on `pos`
+    fn circle(&mut self, pos: Vec2, radius: f32, tint: Tint);
+
+    /// Fill a circle of `radius` centered on `pos` that lets what's under it show through.
+    /// `opacity` is how much of `tint` there is, from 0.0 (invisible) to 1.0 (solid).
+    fn translucent_circle(&mut self, pos: Vec2, radius: f32, tint: Tint, opacity: f32);
+
+    /// Fill a rectangle centered on `pos`
+    fn rectangle(&mut self, pos: Vec2, rectangle: Rectangle, tint: Tint);
+
+    /// Fill in the blocks that make up a label's text
+    fn label(&mut self, label: &Label) {
+        for block in label.blocks() {
+            let rectangle = Rectangle {
+                half_size: block.half_size,
+                angle: 0.,
+            };
+            self.rectangle(block.center, rectangle, label.tint);
+        }
+    }
+}
+
+/// One frame of a game
+pub struct Scene<'a> {
+    pub game: &'a Game,
+    /// Written over everything else
+    pub labels: &'a [Label],
+    /// The best run on the level, to draw alongside the player
+    pub ghost: Option<&'a Ghost>,
+    /// Ticks into the current attempt, for where the ghost is
+    pub tick: u32,
+    /// How far from the last tick to the next one things that move are drawn
+    pub alpha: f32,
+}
+
+impl<'a> Scene<'a> {
+    /// Just `game`, as it is after its latest tick
+    pub fn new(game: &'a Game) -> Self {
+        Self {
+            game,
+            labels: &[],
+            ghost: None,
+            tick: 0,
+            alpha: 1.,
+        }
+    }
+
+    /// Draw the whole frame, from `start` to `finish`
+    pub fn draw(&self, renderer: &mut impl Renderer) {
+        let game = self.game;
+        let world = game.world();
+        let alpha = self.alpha;
+        renderer.start();
+
+        // Draw the Goal
+        for (pos, radius, tint) in <(Read<Position>, Read<Radius>, Read<Tint>)>::query()
+            .filter(tag_value(&Goal))
+            .iter(world)
+        {
+            renderer.circle(*pos, radius.0, *tint);
+        }
+
+        // Draw the Obstacles
+        for (pos, prev, radius, tint) in
+            <(Read<Position>, Read<PrevPosition>, Read<Radius>, Read<Tint>)>::query()
+                .filter(tag_value(&Obstacle))
+                .iter(world)
+        {
+            renderer.circle(glm::lerp(&prev.0, &pos, alpha), radius.0, *tint);
+        }
+
+        // Draw the Walls
+        for (pos, rectangle, tint) in <(Read<Position>, Read<Rectangle>, Read<Tint>)>::query()
+            .filter(tag_value(&Obstacle))
+            .iter(world)
+        {
+            renderer.rectangle(*pos, *rectangle, *tint);
+        }
+
+        // Draw the ghost of the best run under the live player, and how far ahead or behind it the
+        // live player is as a bar in the top right: green when ahead, red when behind
+        if let Some(ghost) = self.ghost {
+            let prev = ghost.pos_at(self.tick.saturating_sub(1));
+            let pos = ghost.pos_at(self.tick);
+            // Don't smear a ghost that just wrapped around across the whole arena
+            let pos = if glm::distance(&prev, &pos) < 0.5 {
+                glm::lerp(&prev, &pos, alpha)
+            } else {
+                pos
+            };
+            renderer.translucent_circle(pos, game.player_radius(), Tint::PLAYER, GHOST_OPACITY);
+
+            let split = ghost.split(self.tick, game.player_pos());
+            let length = (split.abs().min(SPLIT_RANGE) * 10.).round() / 10. / SPLIT_RANGE * 0.5;
+            if length > 0. {
+                let tint = if split < 0. {
+                    Tint::GOAL
+                } else {
+                    Tint::OBSTACLE
+                };
+                let rectangle = Rectangle {
+                    half_size: Vec2::new(length / 2., LIFE_CIRCLE_RADIUS / 2.),
+                    angle: 0.,
+                };
+                let pos = Position::new(
+                    1. - hud::PIXEL * 2. - length / 2.,
+                    hud::split_bar_top() - LIFE_CIRCLE_RADIUS / 2.,
+                );
+                renderer.rectangle(pos, rectangle, tint);
+            }
+        }
+
+        // Draw the Player, blinking while invulnerable
+        let blink_off =
+            game.invulnerable() > 0. && (game.invulnerable() * BLINK_RATE) as u32 % 2 == 1;
+        for (pos, prev, radius, tint) in
+            <(Read<Position>, Read<PrevPosition>, Read<Radius>, Read<Tint>)>::query()
+                .filter(tag_value(&Player))
+                .iter(world)
+        {
+            if blink_off {
+                continue;
+            }
+            renderer.circle(glm::lerp(&prev.0, &pos, alpha), radius.0, *tint);
+        }
+
+        // Draw the life circles
+        for pos in hud::life_icons(game) {
+            renderer.circle(pos, LIFE_CIRCLE_RADIUS, Tint::PLAYER);
+        }
+
+        // Draw the enemies
+        for (pos, prev, radius, tint) in
+            <(Read<Position>, Read<PrevPosition>, Read<Radius>, Read<Tint>)>::query()
+                .filter(tag_value(&Enemy))
+                .iter(world)
+        {
+            renderer.circle(glm::lerp(&prev.0, &pos, alpha), radius.0, *tint);
+        }
+
+        // Write the HUD over everything
+        for label in self.labels {
+            renderer.label(label);
+        }
+
+        renderer.finish();
+    }
+}
+
+/// A grid of cells covering the arena, each standing for the point in its middle
+#[derive(Clone, Debug, PartialEq)]
+struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Copy> Grid<T> {
+    fn new(width: usize, height: usize, empty: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![empty; width * height],
+        }
+    }
+
+    /// The arena position in the middle of the cell at `column`, `row` (row 0 is the top)
+    fn point(&self, column: usize, row: usize) -> Vec2 {
+        Vec2::new(
+            (column as f32 + 0.5) / self.width as f32 * 2. - 1.,
+            1. - (row as f32 + 0.5) / self.height as f32 * 2.,
+        )
+    }
+
+    /// Half the size of a cell, whichever way is bigger
+    fn half_cell(&self) -> f32 {
+        (1. / self.width as f32).max(1. / self.height as f32)
+    }
+
+    /// Change every cell with `apply` whose middle is `hit`, given how far it is from the surface
+    /// of `shape` at `center`
+    fn paint(
+        &mut self,
+        shape: Shape,
+        center: Vec2,
+        hit: impl Fn(f32) -> bool,
+        apply: impl Fn(&mut T),
+    ) {
+        // Only look at the cells that could possibly be hit
+        let reach = shape.bounding_radius() + self.half_cell();
+        let column = |x: f32| (x + 1.) / 2. * self.width as f32;
+        let row = |y: f32| (1. - y) / 2. * self.height as f32;
+        let columns = column(center[0] - reach).max(0.) as usize
+            ..(column(center[0] + reach).ceil().max(0.) as usize).min(self.width);
+        let rows = row(center[1] + reach).max(0.) as usize
+            ..(row(center[1] - reach).ceil().max(0.) as usize).min(self.height);
+        for row in rows {
+            for column in columns.clone() {
+                if hit(shape.distance(center, self.point(column, row))) {
+                    apply(&mut self.cells[row * self.width + column]);
+                }
+            }
+        }
+    }
+
+    fn circle(&mut self, pos: Vec2, radius: f32, value: T) {
+        self.blend_circle(pos, radius, |cell| *cell = value);
+    }
+
+    /// Mix `blend` into the cells inside a circle, instead of just covering them
+    fn blend_circle(&mut self, pos: Vec2, radius: f32, blend: impl Fn(&mut T)) {
+        self.paint(
+            Shape::Circle { radius },
+            pos,
+            |distance| distance <= 0.,
+            blend,
+        );
+    }
+
+    fn rectangle(&mut self, pos: Vec2, rectangle: Rectangle, value: T) {
+        let shape = Shape::Rect {
+            half_size: rectangle.half_size,
+            angle: rectangle.angle,
+        };
+        self.paint(shape, pos, |distance| distance <= 0., |cell| *cell = value);
+    }
+}
+
+/// Pixels in memory, saved as PNG.  Starts out black.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Canvas {
+    grid: Grid<[u8; 3]>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            grid: Grid::new(width, height, [0; 3]),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height
+    }
+
+    /// The red, green and blue of the pixel at `x`, `y`, counting from the top left
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        self.grid.cells[y * self.grid.width + x]
+    }
+
+    /// Every pixel, a row at a time from the top, as red, green and blue bytes
+    pub fn rgb(&self) -> Vec<u8> {
+        self.grid.cells.concat()
+    }
+
+    /// Encode the canvas as a PNG file
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut png = vec![];
+        let mut encoder = png::Encoder::new(&mut png, self.width() as u32, self.height() as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.rgb()))
+            .expect("writing a PNG to memory can't fail");
+        png
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_png())
+    }
+}
+
+/// Each of red, green and blue from 0 to 255
+fn rgb(tint: Tint) -> [u8; 3] {
+    tint.0
+        .map(|channel| (channel.clamp(0., 1.) * 255.).round() as u8)
+}
+
+/// `opacity` of the way from `under` to `over`
+fn mix(under: [u8; 3], over: [u8; 3], opacity: f32) -> [u8; 3] {
+    let opacity = opacity.clamp(0., 1.);
+    let mut mixed = under;
+    for (channel, over) in mixed.iter_mut().zip(over) {
+        *channel = (*channel as f32 * (1. - opacity) + over as f32 * opacity).round() as u8;
+    }
+    mixed
+}
+
+impl Renderer for Canvas {
+    fn start(&mut self) {
+        self.grid.cells.fill([0; 3]);
+    }
+
+    fn circle(&mut self, pos: Vec2, radius: f32, tint: Tint) {
+        self.grid.circle(pos, radius, rgb(tint));
+    }
+
+    fn translucent_circle(&mut self, pos: Vec2, radius: f32, tint: Tint, opacity: f32) {
+        let over = rgb(tint);
+        self.grid
+            .blend_circle(pos, radius, |cell| *cell = mix(*cell, over, opacity));
+    }
+
+    fn rectangle(&mut self, pos: Vec2, rectangle: Rectangle, tint: Tint) {
+        self.grid.rectangle(pos, rectangle, rgb(tint));
+    }
+}
+
+/// A grid of characters, one for each kind of thing, printed a row per line:
+///
+/// | Character | Tint          |
+/// | --------- | ------------- |
+/// | `.`       | Nothing       |
+/// | `G`       | Goal          |
+/// | `P`       | Player        |
+/// | `o`       | See-through   |
+/// | `#`       | Obstacle      |
+/// | `E`       | Enemy         |
+/// | `*`       | Text          |
+/// | `?`       | Anything else |
+///
+/// Anything translucent, like the ghost of a best run, is drawn as `o` whatever its tint.
+///
+/// Terminal characters are about twice as tall as they are wide, so a grid twice as wide as it is
+/// tall looks about square.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ascii {
+    grid: Grid<char>,
+}
+
+impl Ascii {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            grid: Grid::new(width, height, '.'),
+        }
+    }
+}
+
+/// The character each tint is drawn with
+const SYMBOLS: [(Tint, char); 5] = [
+    (Tint::GOAL, 'G'),
+    (Tint::PLAYER, 'P'),
+    (Tint::OBSTACLE, '#'),
+    (Tint::ENEMY, 'E'),
+    (Tint::TEXT, '*'),
+];
+
+/// The character to draw `tint` with
+fn symbol(tint: Tint) -> char {
+    SYMBOLS
+        .iter()
+        .find(|(known, _)| *known == tint)
+        .map_or('?', |&(_, symbol)| symbol)
+}
+
+impl Renderer for Ascii {
+    fn start(&mut self) {
+        self.grid.cells.fill('.');
+    }
+
+    fn circle(&mut self, pos: Vec2, radius: f32, tint: Tint) {
+        self.grid.circle(pos, radius, symbol(tint));
+    }
+
+    fn translucent_circle(&mut self, pos: Vec2, radius: f32, _tint: Tint, _opacity: f32) {
+        self.grid.circle(pos, radius, 'o');
+    }
+
+    fn rectangle(&mut self, pos: Vec2, rectangle: Rectangle, tint: Tint) {
+        self.grid.rectangle(pos, rectangle, symbol(tint));
+    }
+}
+
+impl fmt::Display for Ascii {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.grid.cells.chunks(self.grid.width) {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
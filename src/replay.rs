@@ -0,0 +1,274 @@
+//! Recording runs and playing them back.  A replay holds everything a `Game` is built from (the
+//! config, the level and the starting life) plus the input for every tick, so playing it back
+//! reproduces the run exactly.
+//!
+//! Replays are saved as JSON.  Input is stored as runs of ticks with the same direction, since the
+//! player mostly holds a direction for a while:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "tick_rate": 120,
+//!   "config": { ... },
+//!   "level": { ... },
+//!   "life": 10,
+//!   "inputs": [{ "direction": [1.0, 0.0], "ticks": 240 }, ...],
+//!   "physics": [{ "tick": 300, "physics": { ... } }]
+//! }
+//! ```
+//!
+//! `physics` lists the changes made to the physics file while the run was being recorded.
+//! Replays from a different format version or tick rate are refused rather than played back wrong,
+//! and so are ones with a level or physics that wouldn't load from a file.
+
+use crate::game::{Game, GameConfig, GameEvent, Input};
+use crate::level::Level;
+use crate::physics::PhysicsConfig;
+use crate::storage::{self, FileError};
+use crate::timestep::{TICK, TICK_RATE};
+use rusty_core::glm::Vec2;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Bump this whenever a change to the format or the simulation would make old replays play back
+/// differently
+pub const REPLAY_VERSION: u32 = 1;
+
+/// A recorded run
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Replay {
+    pub version: u32,
+    /// Ticks per second the run was simulated at
+    pub tick_rate: u32,
+    pub config: GameConfig,
+    pub level: Level,
+    /// Life the run started with
+    pub life: i32,
+    pub inputs: Vec<Span>,
+    /// Physics changes made partway through, in tick order
+    #[serde(default)]
+    pub physics: Vec<PhysicsChange>,
+}
+
+/// The same input held for a number of ticks in a row
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Span {
+    pub direction: [f32; 2],
+    pub ticks: u32,
+}
+
+/// The physics changed just before this tick
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PhysicsChange {
+    pub tick: u32,
+    pub physics: PhysicsConfig,
+}
+
+impl Replay {
+    /// Start recording `game`, which shouldn't have been stepped yet
+    pub fn new(game: &Game) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            tick_rate: TICK_RATE,
+            config: game.config().clone(),
+            level: game.level().clone(),
+            life: game.life(),
+            inputs: vec![],
+            physics: vec![],
+        }
+    }
+
+    /// How many ticks have been recorded
+    pub fn ticks(&self) -> u32 {
+        self.inputs.iter().map(|span| span.ticks).sum()
+    }
+
+    /// Record the input for one more tick
+    pub fn record(&mut self, input: &Input) {
+        let direction = [input.direction[0], input.direction[1]];
+        match self.inputs.last_mut() {
+            // Compare bits, so that what gets merged is exactly what would have been stored
+            Some(span) if bits(span.direction) == bits(direction) => span.ticks += 1,
+            _ => self.inputs.push(Span {
+                direction,
+                ticks: 1,
+            }),
+        }
+    }
+
+    /// Record that the physics changed before the next tick
+    pub fn record_physics(&mut self, physics: PhysicsConfig) {
+        let tick = self.ticks();
+        if tick == 0 {
+            self.config.physics = physics;
+            return;
+        }
+        match self.physics.last_mut() {
+            Some(change) if change.tick == tick => change.physics = physics,
+            _ => self.physics.push(PhysicsChange { tick, physics }),
+        }
+    }
+
+    /// A fresh game, just like the one the recording started with
+    pub fn game(&self) -> Game {
+        Game::with_level(self.config.clone(), self.level.clone()).with_life(self.life)
+    }
+
+    /// Play back the recorded input from the start
+    pub fn playback(&self) -> Playback<'_> {
+        Playback {
+            replay: self,
+            tick: 0,
+            span: 0,
+            offset: 0,
+            physics: 0,
+        }
+    }
+
+    /// Play the whole replay without drawing it, returning the game as it ended up and everything
+    /// that happened along the way
+    pub fn run(&self) -> (Game, Vec<GameEvent>) {
+        let mut game = self.game();
+        let mut playback = self.playback();
+        let mut events = vec![];
+        while let Some(tick_events) = playback.step(&mut game) {
+            events.extend(tick_events);
+        }
+        (game, events)
+    }
+
+    /// Load a replay, refusing it if it was made by an incompatible version of the game
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FileError> {
+        storage::load_file(path.as_ref(), Self::parse)
+    }
+
+    /// Parse the contents of a replay file
+    pub fn parse(text: &str) -> Result<Self, FileError> {
+        let parse_error = |err: serde_json::Error| FileError::Parse {
+            path: PathBuf::new(),
+            kind: "replay",
+            message: err.to_string(),
+        };
+        let invalid = |field: &str, message: String| FileError::Invalid {
+            path: PathBuf::new(),
+            field: field.to_string(),
+            message,
+        };
+        // Check the version on its own first, since the rest may not parse at all if it's wrong
+        let header: Header = serde_json::from_str(text).map_err(parse_error)?;
+        if header.version != REPLAY_VERSION {
+            return Err(invalid(
+                "version",
+                format!(
+                    "replay is format version {}, but this build can only play version {}",
+                    header.version, REPLAY_VERSION
+                ),
+            ));
+        }
+        let replay: Replay = serde_json::from_str(text).map_err(parse_error)?;
+        if replay.tick_rate != TICK_RATE {
+            return Err(invalid(
+                "tick_rate",
+                format!(
+                    "replay was recorded at {} ticks per second, but this build runs at {}",
+                    replay.tick_rate, TICK_RATE
+                ),
+            ));
+        }
+        // The file may have been edited since it was recorded, so hold the level and physics in it
+        // to the same rules as their own files
+        replay.level.validate().map_err(|err| err.within("level"))?;
+        replay
+            .config
+            .physics
+            .validate()
+            .map_err(|err| err.within("config.physics"))?;
+        for (i, change) in replay.physics.iter().enumerate() {
+            change
+                .physics
+                .validate()
+                .map_err(|err| err.within(&format!("physics[{}].physics", i)))?;
+        }
+        Ok(replay)
+    }
+
+    /// Save the replay, creating parent directories as needed
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let text = serde_json::to_string(self)?;
+        fs::write(path, text)
+    }
+}
+
+/// `path` with `-<number>` added before the extension, so that each level of a campaign can be
+/// recorded to its own file: `runs/best.json` becomes `runs/best-2.json`
+pub fn numbered_path(path: &Path, number: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, number, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, number),
+    };
+    path.with_file_name(name)
+}
+
+/// Feeds a replay's input to a game one tick at a time
+pub struct Playback<'a> {
+    replay: &'a Replay,
+    /// Ticks played so far
+    tick: u32,
+    /// Index into `replay.inputs` of the span being played
+    span: usize,
+    /// Ticks already played from that span
+    offset: u32,
+    /// Index into `replay.physics` of the next change
+    physics: usize,
+}
+
+impl Playback<'_> {
+    /// Ticks played so far
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.span >= self.replay.inputs.len()
+    }
+
+    /// Step `game` by the next tick of recorded input, returning what happened, or `None` once
+    /// every tick has been played
+    pub fn step(&mut self, game: &mut Game) -> Option<Vec<GameEvent>> {
+        while let Some(change) = self.replay.physics.get(self.physics) {
+            if change.tick > self.tick {
+                break;
+            }
+            game.set_physics(change.physics);
+            self.physics += 1;
+        }
+        let span = self.replay.inputs.get(self.span)?;
+        let input = Input {
+            direction: Vec2::new(span.direction[0], span.direction[1]),
+        };
+        self.offset += 1;
+        if self.offset >= span.ticks {
+            self.span += 1;
+            self.offset = 0;
+        }
+        self.tick += 1;
+        Some(game.step(&input, TICK))
+    }
+}
+
+/// Just enough of a replay to tell which version it is
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+fn bits(direction: [f32; 2]) -> [u32; 2] {
+    [direction[0].to_bits(), direction[1].to_bits()]
+}
@@ -0,0 +1,198 @@
+//! Scores for winning runs, and the table of the best ones on each layout.
+//!
+//! A win scores points for finishing quickly, for life left over and for near misses, and the total
+//! is then scaled by how hard the difficulty was:
+//!
+//! | For         | Points                                                  |
+//! | ----------- | ------------------------------------------------------- |
+//! | Time        | 6000, less 100 for every second taken                   |
+//! | Life left   | 250 each                                                |
+//! | Near misses | 50 each                                                 |
+//! | Difficulty  | x0.5 easy, x1 normal or custom, x1.5 hard, x2 nightmare |
+//!
+//! A near miss is something coming within `NEAR_MISS_MARGIN` of the player and going away again
+//! without touching.
+
+use crate::difficulty::Difficulty;
+use crate::game::Game;
+use crate::level::Level;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How many scores each layout's table keeps
+pub const TABLE_SIZE: usize = 10;
+
+const TIME_POINTS: f32 = 6000.;
+const POINTS_LOST_PER_SECOND: f32 = 100.;
+const LIFE_POINTS: u32 = 250;
+const NEAR_MISS_POINTS: u32 = 50;
+
+/// One winning run
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Score {
+    pub points: u32,
+    /// Seconds it took to win
+    pub seconds: f32,
+    pub life: i32,
+    pub near_misses: u32,
+    pub difficulty: Difficulty,
+    /// The seed the session started from
+    #[serde(with = "storage::u64_string")]
+    pub seed: u64,
+    /// When the run was won, in seconds since the Unix epoch
+    pub when: u64,
+}
+
+impl Score {
+    /// Score a run that took `seconds` to win
+    pub fn new(seconds: f32, life: i32, near_misses: u32, difficulty: Difficulty) -> Self {
+        let time = (TIME_POINTS - seconds * POINTS_LOST_PER_SECOND).max(0.);
+        let bonus = life.max(0) as u32 * LIFE_POINTS + near_misses * NEAR_MISS_POINTS;
+        let multiplier = match difficulty {
+            Difficulty::Easy => 0.5,
+            Difficulty::Normal | Difficulty::Custom => 1.,
+            Difficulty::Hard => 1.5,
+            Difficulty::Nightmare => 2.,
+        };
+        Self {
+            points: ((time + bonus as f32) * multiplier).round() as u32,
+            seconds,
+            life,
+            near_misses,
+            difficulty,
+            seed: 0,
+            when: 0,
+        }
+    }
+
+    /// Score `game`, which the player just won, stamped with the current time
+    pub fn of(game: &Game) -> Self {
+        let when = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        Self {
+            seed: game.seed(),
+            when,
+            ..Self::new(
+                game.time(),
+                game.life(),
+                game.near_misses(),
+                game.config().difficulty,
+            )
+        }
+    }
+}
+
+/// The best scores on one layout, best first
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Table {
+    /// The level's name, to show in listings
+    pub name: String,
+    pub scores: Vec<Score>,
+}
+
+/// Every layout's table, saved between runs
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct HighScores {
+    /// Layout key (see `Level::key`) -> its table
+    pub levels: BTreeMap<String, Table>,
+}
+
+impl HighScores {
+    /// Where high scores are saved by default
+    pub fn default_path() -> Option<PathBuf> {
+        storage::data_dir().map(|dir| dir.join("scores.toml"))
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        storage::load_toml(path)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        storage::save_toml(path, self)
+    }
+
+    /// The table for `level`, best first.  Empty if nobody has won it yet.
+    pub fn table(&self, level: &Level) -> &[Score] {
+        self.levels
+            .get(&level.key())
+            .map_or(&[], |table| &table.scores)
+    }
+
+    /// Add `score` on `level` to its table.  Returns where it placed (0 is the top), or `None` if
+    /// it wasn't good enough to make the table.  Ties go to whoever got there first.
+    pub fn add(&mut self, level: &Level, score: Score) -> Option<usize> {
+        let table = self.levels.entry(level.key()).or_default();
+        table.name = level.name.clone();
+        let rank = table
+            .scores
+            .iter()
+            .position(|other| score.points > other.points)
+            .unwrap_or(table.scores.len());
+        if rank >= TABLE_SIZE {
+            return None;
+        }
+        table.scores.insert(rank, score);
+        table.scores.truncate(TABLE_SIZE);
+        Some(rank)
+    }
+
+    /// Write every score as CSV, one row per score, with a header row
+    pub fn export_csv(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "level,layout,rank,points,seconds,life,near_misses,difficulty,seed,when"
+        )?;
+        for (key, table) in &self.levels {
+            for (rank, score) in table.scores.iter().enumerate() {
+                writeln!(
+                    out,
+                    "{},{},{},{},{:.3},{},{},{},{},{}",
+                    csv_field(&table.name),
+                    key,
+                    rank + 1,
+                    score.points,
+                    score.seconds,
+                    score.life,
+                    score.near_misses,
+                    score.difficulty.name(),
+                    score.seed,
+                    score.when
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Quote `text` for CSV if it needs it
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// `table` laid out for a terminal, with an arrow marking `highlight`
+pub fn format_table(table: &[Score], highlight: Option<usize>) -> String {
+    let mut text = String::from("   #  Points   Time  Life  Near  Difficulty\n");
+    for (rank, score) in table.iter().enumerate() {
+        let marker = if Some(rank) == highlight { '>' } else { ' ' };
+        text += &format!(
+            "{} {:2}  {:6}  {:5.2}  {:4}  {:4}  {}\n",
+            marker,
+            rank + 1,
+            score.points,
+            score.seconds,
+            score.life,
+            score.near_misses,
+            score.difficulty
+        );
+    }
+    text
+}
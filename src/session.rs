@@ -0,0 +1,217 @@
+//! The game's state machine: title screen, playing, paused, won and died, and the moves between
+//! them.  Everything here is pure logic, so the binary only has to turn button presses into
+//! `Action`s and draw whatever state the session is in.
+
+use crate::difficulty::Difficulty;
+use crate::game::{Game, GameConfig, GameEvent, Input};
+use crate::level::Level;
+use crate::physics::PhysicsConfig;
+use crate::replay::Replay;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum State {
+    /// Waiting for the player to start
+    Title,
+    Playing,
+    Paused,
+    /// The player reached the goal
+    Won,
+    /// The player ran out of life or left the arena
+    Died,
+}
+
+/// Discrete things the player can ask for (as opposed to the continuous `Input::direction`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    /// Start from the title screen, or pause/unpause while playing
+    Pause,
+    /// Start from the title screen, or start the same layout over when paused or finished
+    Retry,
+    /// Throw away the current layout and generate a new one.  Levels loaded from a file only have
+    /// the one layout, so for them this is the same as `Retry`.
+    NewLayout,
+    /// Pick the next harder difficulty on the title screen
+    Harder,
+    /// Pick the next easier difficulty on the title screen
+    Easier,
+}
+
+pub struct Session {
+    state: State,
+    game: Game,
+    config: GameConfig,
+    /// The level to play, if it didn't come from `config.seed`
+    level: Option<Level>,
+    /// Life each attempt starts with
+    life: i32,
+    /// Where new seeds come from, so a whole session is reproducible from the first seed
+    seeds: ChaCha8Rng,
+    /// Everything played so far in the current attempt
+    replay: Replay,
+    /// The settings the session started with, if they weren't from a preset.  Choosing `Custom`
+    /// on the title screen goes back to them.
+    custom: Option<GameConfig>,
+}
+
+impl Session {
+    /// Sit at the title screen, ready to play `level`, or a level generated from `config.seed` if
+    /// `level` is `None`
+    pub fn new(config: GameConfig, level: Option<Level>) -> Self {
+        let seeds = ChaCha8Rng::seed_from_u64(config.seed);
+        let life = config.life_max;
+        let game = build_game(&config, &level, life);
+        let custom = Some(config.clone()).filter(|config| config.difficulty == Difficulty::Custom);
+        Self {
+            state: State::Title,
+            replay: Replay::new(&game),
+            game,
+            config,
+            level,
+            life,
+            seeds,
+            custom,
+        }
+    }
+
+    /// Start every attempt with `life` instead of a full `config.life_max`
+    pub fn with_life(mut self, life: i32) -> Self {
+        self.life = life;
+        self.retry();
+        self
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// The settings every attempt starts from
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// The current attempt
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// A recording of the current attempt so far
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Change how things move, both in the current attempt and every one after it
+    pub fn set_physics(&mut self, physics: PhysicsConfig) {
+        self.config.physics = physics;
+        if self.game.config().physics != physics {
+            self.game.set_physics(physics);
+            self.replay.record_physics(physics);
+        }
+    }
+
+    /// Leave the title screen without waiting for the player
+    pub fn start(&mut self) {
+        if self.state == State::Title {
+            self.state = State::Playing;
+        }
+    }
+
+    /// React to something the player asked for.  Returns the new state.
+    pub fn handle(&mut self, action: Action) -> State {
+        use Action::*;
+        use State::*;
+        self.state = match (self.state, action) {
+            (Title, Pause) | (Title, Retry) => Playing,
+            (Title, NewLayout) => {
+                self.new_layout();
+                Title
+            }
+            (Title, Harder) => {
+                self.change_difficulty(1);
+                Title
+            }
+            (Title, Easier) => {
+                self.change_difficulty(-1);
+                Title
+            }
+            (Playing, Pause) => Paused,
+            (Paused, Pause) => Playing,
+            (Paused, Retry) | (Won, Retry) | (Died, Retry) => {
+                self.retry();
+                Playing
+            }
+            (Paused, NewLayout) | (Won, NewLayout) | (Died, NewLayout) => {
+                self.new_layout();
+                Playing
+            }
+            (state, _) => state,
+        };
+        self.state
+    }
+
+    /// Advance the current attempt by `dt` seconds, if we're playing.  Winning or dying moves the
+    /// session into the `Won` or `Died` state.
+    pub fn step(&mut self, input: &Input, dt: f32) -> Vec<GameEvent> {
+        if self.state != State::Playing {
+            return vec![];
+        }
+        self.replay.record(input);
+        let events = self.game.step(input, dt);
+        for event in &events {
+            match event {
+                GameEvent::Won => self.state = State::Won,
+                GameEvent::Died => self.state = State::Died,
+                _ => {}
+            }
+        }
+        events
+    }
+
+    /// Start the same layout over
+    fn retry(&mut self) {
+        self.game = build_game(&self.config, &self.level, self.life);
+        self.replay = Replay::new(&self.game);
+    }
+
+    /// Move `steps` along the list of difficulties, stopping at either end.  `Custom` is only on
+    /// the list if the session started with custom settings.
+    fn change_difficulty(&mut self, steps: isize) {
+        let choices: Vec<Difficulty> = Difficulty::ALL
+            .iter()
+            .copied()
+            .filter(|&difficulty| difficulty != Difficulty::Custom || self.custom.is_some())
+            .collect();
+        let current = choices
+            .iter()
+            .position(|&difficulty| difficulty == self.config.difficulty)
+            .unwrap_or(0) as isize;
+        let chosen = choices[(current + steps).clamp(0, choices.len() as isize - 1) as usize];
+        if chosen == self.config.difficulty {
+            return;
+        }
+        let config = match (&self.custom, chosen) {
+            (Some(custom), Difficulty::Custom) => custom.clone(),
+            _ => chosen.apply(self.config.clone()),
+        };
+        self.life = config.life_max;
+        self.config = config;
+        self.retry();
+    }
+
+    /// Generate a different layout (unless we're playing a fixed level)
+    fn new_layout(&mut self) {
+        if self.level.is_none() {
+            self.config.seed = self.seeds.gen();
+        }
+        self.retry();
+    }
+}
+
+fn build_game(config: &GameConfig, level: &Option<Level>, life: i32) -> Game {
+    let game = match level {
+        Some(level) => Game::with_level(config.clone(), level.clone()),
+        None => Game::new(config.clone()),
+    };
+    game.with_life(life)
+}
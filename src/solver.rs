@@ -0,0 +1,212 @@
+//! Checking that a level can actually be beaten: that the player fits through the gaps between
+//! obstacles all the way from the start to a goal.
+//!
+//! The arena is divided into a grid, and each cell gets a clearance: how far its center is from the
+//! surface of the nearest obstacle, or from the arena's edge if it doesn't wrap.  The player's
+//! center can go anywhere with clearance of at least the player's radius (the configuration space,
+//! with obstacles inflated by the player's size).  Adding cells from the most open to the most
+//! cramped until the start joins up with a goal finds the route whose tightest squeeze is as wide
+//! as possible.
+
+use crate::collision::Shape;
+use crate::components::Position;
+use crate::level::{Boundary, Level};
+use crate::PLAYER_RADIUS;
+use rusty_core::glm::Vec2;
+
+/// Cells along each side of the grid
+const GRID_SIZE: usize = 128;
+/// Clearance is never reported as more than this (see `clearance_limit`).  Anything this open is
+/// no squeeze at all, and not looking any further keeps crowded levels quick to check.
+pub const CLEARANCE_LIMIT: f32 = PLAYER_RADIUS * 2.;
+
+/// What a level's layout allows
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Analysis {
+    /// Whether the player fits through everything between the start and a goal
+    pub solvable: bool,
+    /// Diameter of the biggest circle that can get from the start to a goal: the width of the
+    /// narrowest passage on the best route.  Capped at twice the clearance limit.  `None` if the
+    /// start or every goal is outside the arena.
+    pub narrowest: Option<f32>,
+}
+
+/// Work out whether `level` can be beaten
+pub fn analyze(level: &Level) -> Analysis {
+    // Each obstacle only lowers the clearance of the cells close enough for it to matter
+    let limit = clearance_limit(level);
+    let mut clearance = vec![limit; GRID_SIZE * GRID_SIZE];
+    let centers: Vec<f32> = (0..GRID_SIZE).map(|i| cell_center(i)[0]).collect();
+    // Moving obstacles get out of the way sooner or later, so only the ones that stay put can wall
+    // the player in.  A pulsing one has to be judged at its biggest.
+    for obstacle in level.obstacles.iter().filter(|o| o.motion.is_none()) {
+        let pos = Position::from(obstacle.pos);
+        let radius = obstacle
+            .pulse
+            .map_or(obstacle.radius, |pulse| pulse.radius.max(obstacle.radius));
+        let reach = Vec2::repeat(radius + limit);
+        let (min, max) = (cell_coords(pos - reach), cell_coords(pos + reach));
+        for y in min.1..=max.1 {
+            let dy = centers[y] - pos[1];
+            for x in min.0..=max.0 {
+                let dx = centers[x] - pos[0];
+                let cell = &mut clearance[y * GRID_SIZE + x];
+                *cell = cell.min((dx * dx + dy * dy).sqrt() - radius);
+            }
+        }
+    }
+
+    for wall in &level.walls {
+        let (pos, half_size, angle) = wall.rectangle();
+        let shape = Shape::Rect { half_size, angle };
+        let reach = Vec2::repeat(shape.bounding_radius() + limit);
+        let (min, max) = (cell_coords(pos - reach), cell_coords(pos + reach));
+        for y in min.1..=max.1 {
+            for x in min.0..=max.0 {
+                let cell = &mut clearance[y * GRID_SIZE + x];
+                *cell = cell.min(shape.distance(pos, Vec2::new(centers[x], centers[y])));
+            }
+        }
+    }
+
+    // Unless the arena wraps, its edges hem the player in just like an obstacle would
+    if level.boundary != Boundary::Wrap {
+        for y in 0..GRID_SIZE {
+            for x in 0..GRID_SIZE {
+                let edge = 1. - centers[x].abs().max(centers[y].abs());
+                let cell = &mut clearance[y * GRID_SIZE + x];
+                *cell = cell.min(edge);
+            }
+        }
+    }
+
+    let start = cell_at(Position::from(level.player_start));
+    // Touching a goal isn't enough: the player has to get most of the way into it to win
+    let in_goal: Vec<bool> = (0..GRID_SIZE * GRID_SIZE)
+        .map(|cell| {
+            let pos = cell_center(cell);
+            level.goals.iter().any(|goal| {
+                (pos - Position::from(goal.pos)).magnitude()
+                    < (level.player_radius + goal.radius) / 3.
+            })
+        })
+        .collect();
+    let start = match start {
+        Some(start) if in_goal.contains(&true) => start,
+        _ => {
+            return Analysis {
+                solvable: false,
+                narrowest: None,
+            }
+        }
+    };
+
+    // Open up cells from the most room to the least, until the start is connected to a goal.  All
+    // the goal cells are joined to one extra set, so there's only one thing to check against.
+    let goal = GRID_SIZE * GRID_SIZE;
+    let mut order: Vec<usize> = (0..GRID_SIZE * GRID_SIZE).collect();
+    order.sort_unstable_by(|&a, &b| clearance[b].total_cmp(&clearance[a]));
+    let mut sets = DisjointSets::new(GRID_SIZE * GRID_SIZE + 1);
+    let mut open = vec![false; GRID_SIZE * GRID_SIZE];
+    let mut narrowest = None;
+    for cell in order {
+        open[cell] = true;
+        for neighbor in neighbors(cell, level.boundary == Boundary::Wrap) {
+            if open[neighbor] {
+                sets.union(cell, neighbor);
+            }
+        }
+        if in_goal[cell] {
+            sets.union(cell, goal);
+        }
+        if open[start] && sets.find(start) == sets.find(goal) {
+            narrowest = Some(clearance[cell].max(0.) * 2.);
+            break;
+        }
+    }
+
+    Analysis {
+        solvable: narrowest.is_some_and(|width| width > level.player_radius * 2.),
+        narrowest,
+    }
+}
+
+/// The most clearance `analyze` reports for `level`: `CLEARANCE_LIMIT`, or twice the player's
+/// radius if that's bigger
+pub fn clearance_limit(level: &Level) -> f32 {
+    CLEARANCE_LIMIT.max(level.player_radius * 2.)
+}
+
+fn cell_size() -> f32 {
+    2. / GRID_SIZE as f32
+}
+
+fn cell_center(cell: usize) -> Vec2 {
+    let (x, y) = (cell % GRID_SIZE, cell / GRID_SIZE);
+    Vec2::new(
+        -1. + (x as f32 + 0.5) * cell_size(),
+        -1. + (y as f32 + 0.5) * cell_size(),
+    )
+}
+
+/// The cell containing `pos`, if it's in the arena
+fn cell_at(pos: Vec2) -> Option<usize> {
+    let in_arena = pos.iter().all(|v| (-1. ..1.).contains(v));
+    in_arena.then(|| {
+        let (x, y) = cell_coords(pos);
+        y * GRID_SIZE + x
+    })
+}
+
+/// Column and row of the cell containing `pos`, clamped to the grid
+fn cell_coords(pos: Vec2) -> (usize, usize) {
+    let coord = |v: f32| (((v + 1.) / cell_size()).floor().max(0.) as usize).min(GRID_SIZE - 1);
+    (coord(pos[0]), coord(pos[1]))
+}
+
+/// The cells sharing an edge with `cell`.  If the arena `wraps`, cells on its edges are next to the
+/// ones on the opposite edge.
+fn neighbors(cell: usize, wraps: bool) -> impl Iterator<Item = usize> {
+    let (x, y) = (cell % GRID_SIZE, cell / GRID_SIZE);
+    let size = GRID_SIZE as isize;
+    // A coordinate one step along from `v`, if there is one
+    let step = move |v: usize, by: isize| {
+        let v = v as isize + by;
+        if (0..size).contains(&v) || wraps {
+            Some(v.rem_euclid(size) as usize)
+        } else {
+            None
+        }
+    };
+    let left = step(x, -1).map(|x| y * GRID_SIZE + x);
+    let right = step(x, 1).map(|x| y * GRID_SIZE + x);
+    let down = step(y, -1).map(|y| y * GRID_SIZE + x);
+    let up = step(y, 1).map(|y| y * GRID_SIZE + x);
+    IntoIterator::into_iter([left, right, down, up]).flatten()
+}
+
+/// Union-find over grid cells
+struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parent[a] = b;
+    }
+}
@@ -0,0 +1,94 @@
+//! A uniform grid over the arena for finding which circles are near a point without checking every
+//! one of them.
+
+use rusty_core::glm::Vec2;
+use std::collections::HashMap;
+
+/// Cell size that works well for the usual obstacle sizes: a few obstacles per cell, and a player
+/// only ever straddles a handful of cells.
+pub const DEFAULT_CELL_SIZE: f32 = 0.125;
+
+/// Circles bucketed by which grid cells their bounding boxes cover.  Cells are hashed rather than
+/// stored in a fixed array, so things outside the [-1, 1] arena work too.
+#[derive(Clone, Debug)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    len: usize,
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Build a hash of `circles` (position, radius), indexed by their position in the slice
+    pub fn from_circles(cell_size: f32, circles: &[(Vec2, f32)]) -> Self {
+        let mut hash = Self::new(cell_size);
+        for (index, &(pos, radius)) in circles.iter().enumerate() {
+            hash.insert(index, pos, radius);
+        }
+        hash
+    }
+
+    /// How many circles have been inserted
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.len = 0;
+    }
+
+    /// Add the circle at `pos` with `radius`, identified by `index`
+    pub fn insert(&mut self, index: usize, pos: Vec2, radius: f32) {
+        let (min, max) = self.cell_range(pos, radius);
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                self.cells.entry((x, y)).or_default().push(index);
+            }
+        }
+        self.len += 1;
+    }
+
+    /// Indices of every circle that might be within `radius` of `pos`, each listed once, in
+    /// ascending order.  This is a broadphase: some of them may turn out not to touch at all.
+    pub fn query(&self, pos: Vec2, radius: f32) -> Vec<usize> {
+        let (min, max) = self.cell_range(pos, radius);
+        let mut found = vec![];
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                if let Some(cell) = self.cells.get(&(x, y)) {
+                    found.extend_from_slice(cell);
+                }
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    /// The first and last cell covered by the bounding box of a circle
+    fn cell_range(&self, pos: Vec2, radius: f32) -> ((i32, i32), (i32, i32)) {
+        let cell = |v: f32| (v / self.cell_size).floor() as i32;
+        (
+            (cell(pos[0] - radius), cell(pos[1] - radius)),
+            (cell(pos[0] + radius), cell(pos[1] + radius)),
+        )
+    }
+}
@@ -0,0 +1,150 @@
+//! Reading and writing the small files the game keeps between runs (campaign progress, etc.), and
+//! the error for files the player hands the game (levels, physics tuning, replays, etc.)
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Directory to keep saved data in, under the platform's data dir (`~/.local/share` on Linux,
+/// `~/Library/Application Support` on macOS, `%APPDATA%` on Windows).
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("r_circlegauntlet"))
+}
+
+/// Load a TOML file, or `T::default()` if it doesn't exist yet
+pub fn load_toml<T: DeserializeOwned + Default>(path: &Path) -> io::Result<T> {
+    match fs::read_to_string(path) {
+        Ok(text) => {
+            toml::from_str(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Save `value` to a TOML file, creating parent directories as needed
+pub fn save_toml<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let text =
+        toml::to_string(value).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    fs::write(path, text)
+}
+
+/// For `#[serde(with = "storage::u64_string")]` on `u64` fields saved to TOML, whose integers can't
+/// go past `i64::MAX`.  The number is saved as a string, and can be read back from either a string
+/// or an integer.
+pub mod u64_string {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Saved {
+            Integer(u64),
+            Text(String),
+        }
+        match Saved::deserialize(deserializer)? {
+            Saved::Integer(value) => Ok(value),
+            Saved::Text(text) => text.parse().map_err(de::Error::custom),
+        }
+    }
+}
+
+/// Why a file couldn't be loaded.  Errors from parsing text that didn't come from a file have an
+/// empty `path`.
+#[derive(Debug)]
+pub enum FileError {
+    /// The file couldn't be read at all
+    Io { path: PathBuf, source: io::Error },
+    /// The file isn't a `kind` file (like "level" or "replay") at all, or doesn't have the right
+    /// fields.  The message includes the line and column of the problem.
+    Parse {
+        path: PathBuf,
+        kind: &'static str,
+        message: String,
+    },
+    /// The file parsed, but a value in it doesn't make sense.  `field` is the path to the value,
+    /// like `obstacle[3].radius`.
+    Invalid {
+        path: PathBuf,
+        field: String,
+        message: String,
+    },
+}
+
+impl FileError {
+    /// Attach the path of the file the error came from
+    fn at(mut self, file: &Path) -> Self {
+        match &mut self {
+            FileError::Io { path, .. }
+            | FileError::Parse { path, .. }
+            | FileError::Invalid { path, .. } => *path = file.to_owned(),
+        }
+        self
+    }
+
+    /// The same error, about a value inside `parent` rather than at the top of the file
+    pub fn within(self, parent: &str) -> Self {
+        match self {
+            FileError::Invalid {
+                path,
+                field,
+                message,
+            } => FileError::Invalid {
+                path,
+                field: format!("{}.{}", parent, field),
+                message,
+            },
+            err => err,
+        }
+    }
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let path = match self {
+            FileError::Io { path, .. }
+            | FileError::Parse { path, .. }
+            | FileError::Invalid { path, .. } => path,
+        };
+        if !path.as_os_str().is_empty() {
+            write!(f, "{}: ", path.display())?;
+        }
+        match self {
+            FileError::Io { source, .. } => write!(f, "{}", source),
+            FileError::Parse { kind, message, .. } => {
+                write!(f, "not a valid {} file: {}", kind, message)
+            }
+            FileError::Invalid { field, message, .. } => write!(f, "{}: {}", field, message),
+        }
+    }
+}
+
+impl std::error::Error for FileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FileError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Read `path` and `parse` what's in it, attaching the path to any error
+pub fn load_file<T>(
+    path: &Path,
+    parse: impl FnOnce(&str) -> Result<T, FileError>,
+) -> Result<T, FileError> {
+    let text = fs::read_to_string(path).map_err(|source| FileError::Io {
+        path: path.to_owned(),
+        source,
+    })?;
+    parse(&text).map_err(|err| err.at(path))
+}
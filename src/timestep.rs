@@ -0,0 +1,50 @@
+/// How many times per second the simulation is stepped
+pub const TICK_RATE: u32 = 120;
+/// Length of a single simulation step, in seconds
+pub const TICK: f32 = 1. / TICK_RATE as f32;
+/// Longest stretch of real time we'll try to catch up on in one frame.  Anything beyond this (a
+/// debugger pause, dragging the window around, etc.) is simply dropped so we don't spiral.
+const MAX_FRAME_TIME: f32 = 0.25;
+
+/// Accumulates real elapsed time and hands it back out as a whole number of fixed-size ticks, so
+/// the simulation sees the exact same `dt` every step no matter how fast the machine renders.
+pub struct FixedTimestep {
+    tick: f32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub fn new(tick: f32) -> Self {
+        Self {
+            tick,
+            accumulator: 0.,
+        }
+    }
+
+    pub fn tick(&self) -> f32 {
+        self.tick
+    }
+
+    /// Add `elapsed` seconds of real time and return how many ticks should be simulated now
+    pub fn advance(&mut self, elapsed: f32) -> u32 {
+        self.accumulator += elapsed.min(MAX_FRAME_TIME);
+        let mut ticks = 0;
+        while self.accumulator >= self.tick {
+            self.accumulator -= self.tick;
+            ticks += 1;
+        }
+        ticks
+    }
+
+    /// How far we are between the last simulated tick and the next one, in `[0.0, 1.0)`.  Use it
+    /// to interpolate between previous and current positions when rendering.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.tick
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new(TICK)
+    }
+}
@@ -0,0 +1,138 @@
+use r_circlegauntlet::behavior::*;
+use r_circlegauntlet::glm::{self, Vec2};
+use r_circlegauntlet::level::EnemyKind;
+use r_circlegauntlet::*;
+
+fn surroundings(pos: Vec2, player_pos: Vec2, player_vel: Vec2) -> Surroundings<'static> {
+    Surroundings {
+        pos,
+        vel: Vec2::zeros(),
+        player_pos,
+        player_vel,
+        goals: &[],
+        max_speed: 0.25,
+    }
+}
+
+#[test]
+fn interceptor_leads_the_player() {
+    let sight = surroundings(Vec2::new(0.5, 0.), Vec2::zeros(), Vec2::new(0., 0.2));
+    assert_eq!(Chaser.target(&sight), Some(Vec2::zeros()));
+    let target = Interceptor.target(&sight).unwrap();
+    // Two seconds away at full speed, so it aims two seconds ahead of the player
+    assert!(glm::distance(&target, &Vec2::new(0., 0.4)) < 1e-5);
+}
+
+#[test]
+fn patroller_moves_on_once_it_reaches_a_waypoint() {
+    let mut patroller = Patroller::new(vec![Vec2::new(0.5, 0.), Vec2::new(-0.5, 0.)]);
+    let player = Vec2::new(0.9, 0.9);
+    let sight = surroundings(Vec2::zeros(), player, Vec2::zeros());
+    assert_eq!(patroller.target(&sight), Some(Vec2::new(0.5, 0.)));
+    let sight = surroundings(Vec2::new(0.49, 0.), player, Vec2::zeros());
+    assert_eq!(patroller.target(&sight), Some(Vec2::new(-0.5, 0.)));
+    let sight = surroundings(Vec2::new(-0.5, 0.), player, Vec2::zeros());
+    assert_eq!(patroller.target(&sight), Some(Vec2::new(0.5, 0.)));
+}
+
+#[test]
+fn ambusher_waits_then_chases_for_good() {
+    let mut ambusher = Ambusher::new(0.3);
+    let far = surroundings(Vec2::zeros(), Vec2::new(0.5, 0.), Vec2::zeros());
+    assert_eq!(ambusher.target(&far), None);
+    let near = surroundings(Vec2::zeros(), Vec2::new(0.2, 0.), Vec2::zeros());
+    assert_eq!(ambusher.target(&near), Some(Vec2::new(0.2, 0.)));
+    // Backing off doesn't put it back to sleep
+    assert_eq!(ambusher.target(&far), Some(Vec2::new(0.5, 0.)));
+}
+
+#[test]
+fn goal_guard_circles_the_goal() {
+    let level = Level::parse(
+        r#"
+name = "Guarded"
+player_start = [-0.8, 0.8]
+
+[[goal]]
+pos = [0.5, -0.5]
+
+[[enemy]]
+type = "goal_guard"
+spawn = [0.5, -0.2]
+orbit = 0.3
+"#,
+    )
+    .unwrap();
+    let mut game = Game::with_level(GameConfig::default(), level);
+    let goal = Vec2::new(0.5, -0.5);
+    let mut angles = vec![];
+    for _ in 0..TICK_RATE * 10 {
+        game.step(&Input::default(), TICK);
+        let pos = game.enemy_positions()[0];
+        let distance = glm::distance(&pos, &goal);
+        assert!(
+            distance > 0.1 && distance < 0.5,
+            "wandered {} from the goal",
+            distance
+        );
+        let offset = pos - goal;
+        angles.push(offset[1].atan2(offset[0]));
+    }
+    // It went all the way around at least once
+    let turned: f32 = angles
+        .windows(2)
+        .map(|pair| {
+            let delta = pair[1] - pair[0];
+            (delta + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI
+        })
+        .sum();
+    assert!(
+        turned > std::f32::consts::TAU,
+        "only turned {} radians",
+        turned
+    );
+}
+
+#[test]
+fn patrollers_need_waypoints() {
+    let err = Level::parse(
+        r#"
+name = "Lost"
+player_start = [0.0, 0.0]
+
+[[goal]]
+pos = [0.5, 0.5]
+
+[[enemy]]
+type = "patroller"
+spawn = [-0.5, -0.5]
+"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("enemy[0].waypoints"), "{}", err);
+}
+
+#[test]
+fn generated_enemies_take_turns_with_the_configured_kinds() {
+    let config = GameConfig {
+        enemy_count: 3,
+        enemy_kinds: vec![EnemyKind::Interceptor, EnemyKind::Patroller],
+        ..GameConfig::default()
+    };
+    let level = Level::generate(&config);
+    let kinds: Vec<EnemyKind> = level.enemies.iter().map(|enemy| enemy.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            EnemyKind::Interceptor,
+            EnemyKind::Patroller,
+            EnemyKind::Interceptor
+        ]
+    );
+    assert_eq!(level.enemies[1].waypoints.len(), 2);
+    // And the game can run them
+    let mut game = Game::with_level(config, level);
+    for _ in 0..TICK_RATE {
+        game.step(&Input::default(), TICK);
+    }
+}
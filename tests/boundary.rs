@@ -0,0 +1,100 @@
+use r_circlegauntlet::level::Boundary;
+use r_circlegauntlet::solver;
+use r_circlegauntlet::*;
+
+/// An empty arena with the player near the right edge and the goal far away, plus a patroller
+/// pacing out to the same edge
+fn open_arena(boundary: &str) -> Level {
+    Level::parse(&format!(
+        r#"name = "Open"
+player_start = [0.7, 0.5]
+boundary = "{}"
+
+[[goal]]
+pos = [-0.8, -0.8]
+
+[[enemy]]
+type = "patroller"
+spawn = [0.0, -0.5]
+waypoints = [[1.0, -0.5], [0.0, -0.5]]
+"#,
+        boundary
+    ))
+    .unwrap()
+}
+
+/// Push right for `seconds`, returning every event and how far right any enemy got, and checking
+/// the player and enemies are always wholly inside the arena
+fn push_right(game: &mut Game, seconds: u32) -> (Vec<GameEvent>, f32) {
+    let input = Input {
+        direction: glm::Vec2::new(1., 0.),
+    };
+    let mut events = vec![];
+    let mut furthest = f32::MIN;
+    for _ in 0..TICK_RATE * seconds {
+        events.extend(game.step(&input, TICK));
+        let limit = 1. - PLAYER_RADIUS + 1e-4;
+        assert!(game.player_pos()[0] <= limit, "{:?}", game.player_pos());
+        for pos in game.enemy_positions() {
+            assert!(pos[0] <= 1. - ENEMY_WIDTH * 0.5 + 1e-4, "{:?}", pos);
+            furthest = furthest.max(pos[0]);
+        }
+    }
+    (events, furthest)
+}
+
+#[test]
+fn bouncy_edges_keep_everything_in() {
+    let mut game = Game::with_level(GameConfig::default(), open_arena("bouncy"));
+    let (events, furthest) = push_right(&mut game, 5);
+    assert_eq!(events, vec![]);
+    assert_eq!(game.life(), LIFE_MAX);
+    // The patroller made it all the way out to the edge, and bounced off of it too
+    assert!(furthest > 1. - ENEMY_WIDTH * 0.5 - 1e-3);
+}
+
+#[test]
+fn painful_edges_keep_everything_in_but_hurt() {
+    let mut game = Game::with_level(GameConfig::default(), open_arena("painful"));
+    let (events, _) = push_right(&mut game, 5);
+    assert!(events.contains(&GameEvent::Hit { life: LIFE_MAX - 1 }));
+    assert!(!events.contains(&GameEvent::Died));
+}
+
+#[test]
+fn wrapping_comes_back_in_the_other_side() {
+    let mut game = Game::with_level(GameConfig::default(), open_arena("wrap"));
+    let input = Input {
+        direction: glm::Vec2::new(1., 0.),
+    };
+    let mut wrapped = false;
+    for _ in 0..TICK_RATE * 3 {
+        assert_eq!(game.step(&input, TICK), vec![]);
+        let pos = game.player_pos();
+        assert!(pos[0].abs() <= 1.);
+        wrapped |= pos[0] < 0.;
+    }
+    assert!(wrapped);
+}
+
+#[test]
+fn wrapping_opens_routes_through_the_edges() {
+    // A wall from top to bottom between the player and the goal
+    let mut level = Level::parse(
+        r#"name = "Fenced in"
+player_start = [-0.5, 0.0]
+
+[[goal]]
+pos = [0.5, 0.0]
+
+[[wall]]
+type = "segment"
+from = [0.0, 1.0]
+to = [0.0, -1.0]
+"#,
+    )
+    .unwrap();
+    assert!(!solver::analyze(&level).solvable);
+    level.boundary = Boundary::Wrap;
+    assert!(solver::analyze(&level).solvable);
+}
@@ -0,0 +1,50 @@
+use r_circlegauntlet::campaign::{Advance, LifeRule, Progress};
+use r_circlegauntlet::*;
+use std::path::PathBuf;
+
+fn campaign(life: LifeRule) -> Campaign {
+    let mut campaign = Campaign::load("levels/campaign.toml").unwrap();
+    campaign.levels.truncate(3);
+    campaign.life = life;
+    campaign
+}
+
+#[test]
+fn winning_advances_and_carries_life() {
+    let mut run = CampaignRun::new(campaign(LifeRule::Carry), GameConfig::default(), 0);
+    assert_eq!(run.start_level().unwrap().game().life(), LIFE_MAX);
+    assert_eq!(run.level_won(7), Advance::NextLevel(1));
+    assert_eq!(run.start_level().unwrap().game().life(), 7);
+    assert_eq!(run.level_won(3), Advance::NextLevel(2));
+    assert_eq!(run.start_level().unwrap().game().life(), 3);
+    assert_eq!(run.level_won(3), Advance::Finished);
+}
+
+#[test]
+fn reset_rule_restores_life() {
+    let mut run = CampaignRun::new(campaign(LifeRule::Reset), GameConfig::default(), 0);
+    assert_eq!(run.level_won(2), Advance::NextLevel(1));
+    assert_eq!(run.start_level().unwrap().game().life(), LIFE_MAX);
+}
+
+#[test]
+fn resumes_at_unlocked_level() {
+    let campaign = campaign(LifeRule::Carry);
+    let mut progress = Progress::default();
+    assert_eq!(progress.unlocked(&campaign), 0);
+    progress.unlock(&campaign, 2);
+    progress.unlock(&campaign, 1);
+    assert_eq!(progress.unlocked(&campaign), 2);
+
+    let path: PathBuf = std::env::temp_dir()
+        .join(format!("r_circlegauntlet-{}", std::process::id()))
+        .join("progress.toml");
+    progress.save(&path).unwrap();
+    let loaded = Progress::load(&path).unwrap();
+    assert_eq!(loaded, progress);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+    // Starting past the end clamps to the last level
+    let run = CampaignRun::new(campaign, GameConfig::default(), 99);
+    assert_eq!(run.level_index(), 2);
+}
@@ -0,0 +1,81 @@
+use r_circlegauntlet::cli::{Command, Options};
+
+#[test]
+fn parses_seed() {
+    let options = Options::parse(vec!["--seed", "42"]).unwrap();
+    assert_eq!(options.seed, Some(42));
+    assert_eq!(Options::parse(Vec::<String>::new()).unwrap().seed, None);
+}
+
+#[test]
+fn rejects_bad_seed() {
+    assert!(Options::parse(vec!["--seed"]).is_err());
+    assert!(Options::parse(vec!["--seed", "-1"]).is_err());
+    assert!(Options::parse(vec!["--sneed", "1"]).is_err());
+}
+
+#[test]
+fn parses_validate_level() {
+    let options = Options::parse(vec!["validate-level", "levels/01_first_steps.toml"]).unwrap();
+    assert_eq!(
+        options.command,
+        Command::ValidateLevel("levels/01_first_steps.toml".into())
+    );
+    assert_eq!(
+        Options::parse(Vec::<String>::new()).unwrap().command,
+        Command::Play
+    );
+    assert!(Options::parse(vec!["validate-level"]).is_err());
+}
+
+#[test]
+fn parses_enemy_count() {
+    assert_eq!(
+        Options::parse(vec!["--enemies", "3"]).unwrap().enemies,
+        Some(3)
+    );
+    assert!(Options::parse(vec!["--enemies", "lots"]).is_err());
+    assert!(Options::parse(vec!["--enemies"]).is_err());
+}
+
+#[test]
+fn parses_physics_path() {
+    assert_eq!(
+        Options::parse(vec!["--physics", "tuning.toml"])
+            .unwrap()
+            .physics,
+        Some("tuning.toml".into())
+    );
+    assert!(Options::parse(vec!["--physics"]).is_err());
+}
+
+#[test]
+fn rejects_bad_difficulty_settings() {
+    assert!(Options::parse(vec!["--difficulty", "impossible"]).is_err());
+    assert!(Options::parse(vec!["--lives", "0"]).is_err());
+    assert!(Options::parse(vec!["--obstacles", "many"]).is_err());
+    assert!(Options::parse(vec!["--boundary", "sticky"]).is_err());
+}
+
+#[test]
+fn parses_replay_paths() {
+    let options = Options::parse(vec!["--record", "run.json"]).unwrap();
+    assert_eq!(options.record, Some("run.json".into()));
+    let options = Options::parse(vec!["--replay", "run.json"]).unwrap();
+    assert_eq!(options.replay, Some("run.json".into()));
+    assert!(Options::parse(vec!["--replay"]).is_err());
+}
+
+#[test]
+fn parses_scores() {
+    let options = Options::parse(vec!["scores"]).unwrap();
+    assert_eq!(options.command, Command::Scores { export: None });
+    let options = Options::parse(vec!["scores", "--export", "scores.csv"]).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Scores {
+            export: Some("scores.csv".into())
+        }
+    );
+    assert!(Options::parse(vec!["--export", "scores.csv"]).is_err());
+}
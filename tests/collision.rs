@@ -0,0 +1,254 @@
+use r_circlegauntlet::collision::{self, circle_contact, Body, Shape};
+use r_circlegauntlet::glm::Vec2;
+use r_circlegauntlet::*;
+
+const EPSILON: f32 = 1e-4;
+
+#[test]
+fn contact_normal_points_away_from_the_other_circle() {
+    let contact = circle_contact(Vec2::new(0.15, 0.), 0.1, Vec2::zeros(), 0.1).unwrap();
+    assert!((contact.normal - Vec2::new(1., 0.)).magnitude() < EPSILON);
+    assert!((contact.depth - 0.05).abs() < EPSILON);
+    assert_eq!(
+        circle_contact(Vec2::new(0.3, 0.), 0.1, Vec2::zeros(), 0.1),
+        None
+    );
+}
+
+#[test]
+fn restitution_scales_the_bounce() {
+    let normal = Vec2::new(1., 0.);
+    let vel = Vec2::new(-1., 0.5);
+    assert_eq!(collision::bounce(vel, normal, 1.0), Vec2::new(1., 0.5));
+    assert_eq!(collision::bounce(vel, normal, 0.5), Vec2::new(0.5, 0.5));
+    assert_eq!(collision::bounce(vel, normal, 0.0), Vec2::new(0., 0.5));
+    // Already leaving the surface: nothing to do
+    assert_eq!(collision::bounce(-vel, normal, 0.5), -vel);
+}
+
+#[test]
+fn every_overlap_is_resolved() {
+    // Sitting in the gap between three circles that all overlap it
+    let others = [
+        Body::fixed(Vec2::new(-0.1, 0.), 0.08),
+        Body::fixed(Vec2::new(0.1, 0.), 0.08),
+        Body::fixed(Vec2::new(0., 0.12), 0.08),
+    ];
+    let mut pos = Vec2::new(0., 0.02);
+    let mut vel = Vec2::new(0., 0.3);
+    let touched = collision::resolve_circle(&mut pos, &mut vel, PLAYER_RADIUS, &others, 0.5, 0.);
+    assert_eq!(touched.len(), 3);
+    for other in &others {
+        assert!(
+            other.shape.distance(other.pos, pos) >= PLAYER_RADIUS - EPSILON,
+            "still inside the circle at {:?}",
+            other.pos
+        );
+    }
+    // Pushed back out of the gap it was heading into
+    assert!(vel[1] <= 0.);
+}
+
+#[test]
+fn player_never_ends_a_step_inside_an_obstacle() {
+    // Drive straight into a tight cluster for a while and make sure it never lets us in
+    let level = Level::parse(
+        r#"
+name = "Cluster"
+player_start = [-0.6, 0.0]
+
+[[goal]]
+pos = [0.9, 0.9]
+
+[[obstacle]]
+pos = [0.0, 0.05]
+
+[[obstacle]]
+pos = [0.0, -0.05]
+
+[[obstacle]]
+pos = [0.09, 0.0]
+"#,
+    )
+    .unwrap();
+    let config = GameConfig {
+        life_max: 1000,
+        invulnerability: 0.,
+        physics: PhysicsConfig {
+            restitution: 0.2,
+            ..PhysicsConfig::default()
+        },
+        ..GameConfig::default()
+    };
+    let mut game = Game::with_level(config, level.clone());
+    let input = Input {
+        direction: Vec2::new(1., 0.),
+    };
+    for _ in 0..TICK_RATE * 4 {
+        game.step(&input, TICK);
+        let player = game.player_pos();
+        for obstacle in &level.obstacles {
+            let distance = (player - Vec2::from(obstacle.pos)).magnitude();
+            assert!(
+                distance >= PLAYER_RADIUS + obstacle.radius - EPSILON,
+                "player at {:?} is inside the obstacle at {:?}",
+                player,
+                obstacle.pos
+            );
+        }
+    }
+}
+
+#[test]
+fn time_of_impact_finds_the_first_touch() {
+    // Moving right by 1.0 toward a circle 0.5 away, with 0.1 of combined radius
+    let t = collision::time_of_impact(Vec2::zeros(), Vec2::new(1., 0.), 0.1, Vec2::new(0.5, 0.));
+    assert!((t.unwrap() - 0.4).abs() < EPSILON);
+    // Stops short
+    assert_eq!(
+        collision::time_of_impact(Vec2::zeros(), Vec2::new(0.3, 0.), 0.1, Vec2::new(0.5, 0.)),
+        None
+    );
+    // Moving away
+    assert_eq!(
+        collision::time_of_impact(Vec2::zeros(), Vec2::new(-1., 0.), 0.1, Vec2::new(0.5, 0.)),
+        None
+    );
+    // Already touching
+    assert_eq!(
+        collision::time_of_impact(Vec2::zeros(), Vec2::new(-1., 0.), 0.1, Vec2::new(0.05, 0.)),
+        Some(0.)
+    );
+}
+
+#[test]
+fn a_big_step_cannot_tunnel_through_a_thin_obstacle() {
+    // One step long enough to carry the player clean over the obstacle
+    let others = [Body::fixed(Vec2::zeros(), 0.01)];
+    let mut pos = Vec2::new(-0.5, 0.);
+    let mut vel = Vec2::new(1., 0.);
+    let sweep = collision::sweep_circle(&mut pos, &mut vel, PLAYER_RADIUS, &others, 1.0, 1.0);
+    assert_eq!(sweep.touched, vec![0]);
+    // Bounced back the way it came, and spent the rest of the step going that way
+    assert!(vel[0] < 0.);
+    assert!(pos[0] < -(PLAYER_RADIUS + 0.01));
+    assert_eq!(sweep.path.len(), 3);
+}
+
+#[test]
+fn a_moving_body_hits_a_circle_standing_still() {
+    // Coming from the right fast enough to get all the way across in one step
+    let others = [Body {
+        pos: Vec2::new(0.5, 0.),
+        vel: Vec2::new(-1., 0.),
+        shape: Shape::Circle { radius: 0.05 },
+    }];
+    let mut pos = Vec2::zeros();
+    let mut vel = Vec2::zeros();
+    let sweep = collision::sweep_circle(&mut pos, &mut vel, PLAYER_RADIUS, &others, 0.5, 1.0);
+    assert_eq!(sweep.touched, vec![0]);
+    // Knocked the way the body was going, at least as fast, and not left inside it
+    assert!(vel[0] <= -1.);
+    let other_end = others[0].pos_at(1.0);
+    assert!((pos - other_end).magnitude() >= PLAYER_RADIUS + 0.05 - EPSILON);
+}
+
+#[test]
+fn rectangles_push_out_of_the_nearest_side_or_corner() {
+    let wall = Shape::Rect {
+        half_size: Vec2::new(0.2, 0.05),
+        angle: 0.,
+    };
+    // Just above the top side
+    let contact = wall
+        .contact(Vec2::zeros(), Vec2::new(0.1, 0.08), 0.05)
+        .unwrap();
+    assert!((contact.normal - Vec2::new(0., 1.)).magnitude() < EPSILON);
+    assert!((contact.depth - 0.02).abs() < EPSILON);
+    // Off the corner, diagonally
+    let contact = wall
+        .contact(Vec2::zeros(), Vec2::new(0.22, 0.07), 0.05)
+        .unwrap();
+    assert!((contact.normal - Vec2::new(1., 1.).normalize()).magnitude() < EPSILON);
+    // Deep inside, near the right end: out through the right side
+    let contact = wall
+        .contact(Vec2::zeros(), Vec2::new(0.18, 0.), 0.05)
+        .unwrap();
+    assert!((contact.normal - Vec2::new(1., 0.)).magnitude() < EPSILON);
+    assert!((contact.depth - 0.07).abs() < EPSILON);
+    // Clear of the corner even though inside its bounding box
+    assert_eq!(
+        wall.contact(Vec2::zeros(), Vec2::new(0.24, 0.09), 0.05),
+        None
+    );
+}
+
+#[test]
+fn a_turned_wall_reflects_along_its_own_normal() {
+    // A long wall at 45 degrees, hit from the left while moving right: bounces straight up
+    let walls = [Body::wall(
+        Vec2::new(0.5, 0.),
+        Vec2::new(0.5, 0.01),
+        std::f32::consts::FRAC_PI_4,
+    )];
+    let mut pos = Vec2::new(0., 0.);
+    let mut vel = Vec2::new(1., 0.);
+    let sweep = collision::sweep_circle(&mut pos, &mut vel, PLAYER_RADIUS, &walls, 1.0, 1.0);
+    assert_eq!(sweep.touched, vec![0]);
+    assert!((vel - Vec2::new(0., 1.)).magnitude() < EPSILON, "{:?}", vel);
+}
+
+#[test]
+fn a_big_step_cannot_tunnel_through_a_thin_wall() {
+    let walls = [Body::wall(Vec2::zeros(), Vec2::new(0.002, 0.5), 0.3)];
+    let mut pos = Vec2::new(-0.5, 0.);
+    let mut vel = Vec2::new(2., 0.);
+    let sweep = collision::sweep_circle(&mut pos, &mut vel, PLAYER_RADIUS, &walls, 0.5, 1.0);
+    assert_eq!(sweep.touched, vec![0]);
+    assert!(pos[0] < 0.);
+    assert!(walls[0].shape.distance(walls[0].pos, pos) >= PLAYER_RADIUS - EPSILON);
+}
+
+#[test]
+fn a_big_step_cannot_skip_past_the_goal() {
+    let path = [Vec2::new(-0.5, 0.), Vec2::new(0.5, 0.)];
+    assert!(collision::path_touches(&path, 0.05, Vec2::new(0., 0.02)));
+    assert!(!collision::path_touches(&path, 0.05, Vec2::new(0., 0.2)));
+}
+
+#[test]
+fn outcome_does_not_depend_on_step_size() {
+    // A player drifting through a thin obstacle hits it whether the step is short or long
+    let level = Level::parse(
+        r#"
+name = "Thin"
+player_start = [-0.3, 0.0]
+
+[[goal]]
+pos = [0.9, 0.9]
+
+[[obstacle]]
+pos = [0.0, 0.0]
+radius = 0.005
+"#,
+    )
+    .unwrap();
+    let input = Input {
+        direction: Vec2::new(1., 0.),
+    };
+    for &steps_per_tick in &[1, 8] {
+        let mut game = Game::with_level(GameConfig::default(), level.clone());
+        let mut hit = false;
+        for _ in 0..TICK_RATE * 2 / steps_per_tick {
+            let events = game.step(&input, TICK * steps_per_tick as f32);
+            hit |= events
+                .iter()
+                .any(|event| matches!(event, GameEvent::Hit { .. }));
+        }
+        assert!(
+            hit,
+            "missed the obstacle stepping {} ticks at a time",
+            steps_per_tick
+        );
+    }
+}
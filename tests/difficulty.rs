@@ -0,0 +1,159 @@
+use r_circlegauntlet::cli::Options;
+use r_circlegauntlet::level::{Boundary, EnemyKind, Navigation};
+use r_circlegauntlet::*;
+
+#[test]
+fn normal_is_the_default() {
+    assert_eq!(
+        Difficulty::Normal.apply(GameConfig::default()),
+        GameConfig::default()
+    );
+}
+
+#[test]
+fn presets_get_harder() {
+    let configs: Vec<GameConfig> = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Nightmare,
+    ]
+    .iter()
+    .map(|difficulty| difficulty.apply(GameConfig::default()))
+    .collect();
+    for pair in configs.windows(2) {
+        let (easier, harder) = (&pair[0], &pair[1]);
+        assert!(easier.obstacle_count < harder.obstacle_count);
+        assert!(easier.enemy_count < harder.enemy_count);
+        assert!(easier.enemy_speed < harder.enemy_speed);
+        assert!(easier.life_max > harder.life_max);
+        assert!(easier.invulnerability > harder.invulnerability);
+        assert!(easier.enemy_kinds.len() <= harder.enemy_kinds.len());
+    }
+    // Every preset makes a layout the player can actually get through
+    for config in configs {
+        for seed in 0..5 {
+            let game = Game::new(GameConfig {
+                seed,
+                ..config.clone()
+            });
+            assert!(solver::analyze(game.level()).solvable, "{:?}", config);
+        }
+    }
+}
+
+#[test]
+fn harder_presets_bring_smarter_enemies() {
+    let normal = Difficulty::Normal.apply(GameConfig::default());
+    assert_eq!(normal.enemy_kinds, vec![EnemyKind::Chaser]);
+    assert_eq!(normal.enemy_navigation, Navigation::Bounce);
+
+    let hard = Difficulty::Hard.apply(GameConfig::default());
+    assert!(hard.enemy_kinds.contains(&EnemyKind::Interceptor));
+    assert_eq!(hard.enemy_navigation, Navigation::Pathfind);
+
+    let nightmare = Difficulty::Nightmare.apply(GameConfig::default());
+    assert!(nightmare.enemy_kinds.contains(&EnemyKind::Interceptor));
+    assert!(nightmare.enemy_kinds.contains(&EnemyKind::Ambusher));
+    assert_eq!(nightmare.enemy_navigation, Navigation::Pathfind);
+
+    // ...and the generated layouts use them
+    let game = Game::new(nightmare);
+    let kinds: Vec<EnemyKind> = game
+        .level()
+        .enemies
+        .iter()
+        .map(|enemy| enemy.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            EnemyKind::Interceptor,
+            EnemyKind::Ambusher,
+            EnemyKind::Chaser
+        ]
+    );
+    assert!(game
+        .level()
+        .enemies
+        .iter()
+        .all(|enemy| enemy.navigation == Navigation::Pathfind));
+}
+
+#[test]
+fn custom_keeps_the_settings_it_is_given() {
+    let config = GameConfig {
+        obstacle_count: 3,
+        life_max: 2,
+        ..GameConfig::default()
+    };
+    let custom = Difficulty::Custom.apply(config.clone());
+    assert_eq!(custom.difficulty, Difficulty::Custom);
+    assert_eq!(custom.obstacle_count, 3);
+    assert_eq!(custom.life_max, 2);
+}
+
+#[test]
+fn names_round_trip() {
+    for &difficulty in Difficulty::ALL.iter() {
+        assert_eq!(Difficulty::from_name(difficulty.name()), Some(difficulty));
+    }
+    assert_eq!(Difficulty::from_name("HARD"), Some(Difficulty::Hard));
+    assert_eq!(Difficulty::from_name("impossible"), None);
+}
+
+#[test]
+fn command_line_overrides_make_it_custom() {
+    let options = Options::parse(vec!["--difficulty", "hard"]).unwrap();
+    let config = options.game_config(GameConfig::default());
+    assert_eq!(config, Difficulty::Hard.apply(GameConfig::default()));
+
+    let options = Options::parse(vec!["--difficulty", "hard", "--lives", "3"]).unwrap();
+    let config = options.game_config(GameConfig::default());
+    assert_eq!(config.difficulty, Difficulty::Custom);
+    assert_eq!(config.life_max, 3);
+    assert_eq!(
+        config.obstacle_count,
+        Difficulty::Hard.apply(GameConfig::default()).obstacle_count
+    );
+
+    let options = Options::parse(vec!["--boundary", "wrap", "--obstacles", "4"]).unwrap();
+    let config = options.game_config(GameConfig::default());
+    assert_eq!(config.boundary, Boundary::Wrap);
+    assert_eq!(config.obstacle_count, 4);
+}
+
+#[test]
+fn the_title_screen_picks_the_difficulty() {
+    let mut session = Session::new(GameConfig::default(), None);
+    assert_eq!(session.handle(Action::Harder), State::Title);
+    assert_eq!(session.config().difficulty, Difficulty::Hard);
+    assert_eq!(session.game().life(), session.config().life_max);
+    session.handle(Action::Harder);
+    session.handle(Action::Harder);
+    // Custom isn't on the list unless the session started with custom settings
+    assert_eq!(session.config().difficulty, Difficulty::Nightmare);
+    for _ in 0..5 {
+        session.handle(Action::Easier);
+    }
+    assert_eq!(session.config().difficulty, Difficulty::Easy);
+
+    // Once playing, the difficulty is locked in
+    session.start();
+    session.handle(Action::Harder);
+    assert_eq!(session.config().difficulty, Difficulty::Easy);
+}
+
+#[test]
+fn custom_settings_can_be_picked_again() {
+    let custom = GameConfig {
+        difficulty: Difficulty::Custom,
+        life_max: 2,
+        ..GameConfig::default()
+    };
+    let mut session = Session::new(custom.clone(), None);
+    session.handle(Action::Easier);
+    assert_eq!(session.config().difficulty, Difficulty::Nightmare);
+    session.handle(Action::Harder);
+    assert_eq!(session.config(), &custom);
+}
@@ -0,0 +1,142 @@
+use r_circlegauntlet::*;
+
+#[test]
+fn runs_headless() {
+    let mut game = Game::new(GameConfig::default());
+    assert_eq!(game.life(), LIFE_MAX);
+    let start = game.player_pos();
+    let input = Input {
+        direction: glm::Vec2::new(1.0, 0.0),
+    };
+    for _ in 0..10 {
+        game.step(&input, TICK);
+    }
+    assert!(game.player_pos().x > start.x);
+}
+
+#[test]
+fn leaving_the_arena_kills_the_player() {
+    let mut game = Game::new(GameConfig {
+        obstacle_count: 0,
+        ..GameConfig::default()
+    });
+    let input = Input {
+        direction: glm::Vec2::new(-1.0, 0.0),
+    };
+    let mut events = vec![];
+    for _ in 0..TICK_RATE * 10 {
+        events.extend(game.step(&input, TICK));
+        if game.is_over() {
+            break;
+        }
+    }
+    assert_eq!(events.last(), Some(&GameEvent::Died));
+}
+
+fn obstacle_positions(game: &Game) -> Vec<Position> {
+    use legion::prelude::*;
+    <Read<Position>>::query()
+        .filter(tag_value(&Obstacle))
+        .iter(game.world())
+        .map(|pos| *pos)
+        .collect()
+}
+
+#[test]
+fn same_seed_same_layout() {
+    let config = GameConfig {
+        seed: 1234,
+        ..GameConfig::default()
+    };
+    let a = Game::new(config.clone());
+    let b = Game::new(config);
+    assert_eq!(obstacle_positions(&a), obstacle_positions(&b));
+
+    let c = Game::new(GameConfig {
+        seed: 4321,
+        ..GameConfig::default()
+    });
+    assert_ne!(obstacle_positions(&a), obstacle_positions(&c));
+}
+
+#[test]
+fn enemies_spawn_out_of_reach_of_the_player() {
+    for seed in 0..50 {
+        let config = GameConfig {
+            seed,
+            enemy_count: 4,
+            ..GameConfig::default()
+        };
+        let level = Level::generate(&config);
+        assert_eq!(level.enemies.len(), 4, "seed {}", seed);
+        let start = Position::from(level.player_start);
+        for enemy in &level.enemies {
+            let distance = glm::distance(&start, &Position::from(enemy.spawn));
+            assert!(
+                distance >= config.enemy_spacing.sqrt() && distance > PLAYER_RADIUS + ENEMY_WIDTH,
+                "seed {}: enemy at {:?} spawned {} from the player",
+                seed,
+                enemy.spawn,
+                distance
+            );
+        }
+    }
+}
+
+#[test]
+fn enemies_spawn_where_the_layout_says() {
+    let config = GameConfig {
+        seed: 3,
+        enemy_count: 2,
+        ..GameConfig::default()
+    };
+    let level = Level::generate(&config);
+    let game = Game::with_level(config, level.clone());
+    let mut spawns: Vec<[f32; 2]> = game.enemy_positions().into_iter().map(Into::into).collect();
+    let mut expected: Vec<[f32; 2]> = level.enemies.iter().map(|enemy| enemy.spawn).collect();
+    spawns.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(spawns, expected);
+    assert_ne!(expected[0], [0.75, 0.75]);
+}
+
+#[test]
+fn collisions_use_the_player_radius() {
+    // A small obstacle just below the player's path
+    let level = |player_radius: f32| {
+        Level::parse(&format!(
+            r#"name = "Sizes"
+player_start = [-0.5, 0.0]
+player_radius = {}
+
+[[goal]]
+pos = [0.8, 0.8]
+
+[[obstacle]]
+pos = [0.0, -0.12]
+radius = 0.02
+"#,
+            player_radius
+        ))
+        .unwrap()
+    };
+    let input = Input {
+        direction: glm::Vec2::new(1.0, 0.0),
+    };
+    let hits = |player_radius: f32| {
+        let mut game = Game::with_level(GameConfig::default(), level(player_radius));
+        assert_eq!(game.player_radius(), player_radius);
+        let mut hits = 0;
+        for _ in 0..TICK_RATE * 2 {
+            hits += game
+                .step(&input, TICK)
+                .iter()
+                .filter(|event| matches!(event, GameEvent::Hit { .. }))
+                .count();
+        }
+        hits
+    };
+    // A small player slips past the obstacle, a big one clips it
+    assert_eq!(hits(0.05), 0);
+    assert_eq!(hits(0.15), 1);
+}
@@ -0,0 +1,83 @@
+use r_circlegauntlet::ghost::{BestRuns, Ghost};
+use r_circlegauntlet::*;
+use std::path::PathBuf;
+
+/// A straight shot to the goal
+fn runway() -> Level {
+    Level::parse(
+        r#"name = "Runway"
+player_start = [-0.5, 0.0]
+
+[[goal]]
+pos = [0.5, 0.0]
+"#,
+    )
+    .unwrap()
+}
+
+/// Win the runway after standing still for `dawdle` ticks, returning the recording
+fn win(dawdle: u32) -> Replay {
+    let mut session = Session::new(GameConfig::default(), Some(runway()));
+    session.start();
+    for _ in 0..dawdle {
+        session.step(&Input::default(), TICK);
+    }
+    let right = Input {
+        direction: glm::Vec2::new(1., 0.),
+    };
+    for _ in 0..TICK_RATE * 10 {
+        if session.step(&right, TICK).contains(&GameEvent::Won) {
+            return session.replay().clone();
+        }
+    }
+    panic!("never reached the goal");
+}
+
+#[test]
+fn the_ghost_follows_the_recorded_path() {
+    let replay = win(0);
+    let ghost = Ghost::from_replay(&replay);
+    assert_eq!(ghost.ticks(), replay.ticks());
+    let (game, _) = replay.run();
+    assert_eq!(ghost.pos_at(ghost.ticks()), game.player_pos());
+    // Once it's finished it waits at the goal
+    assert_eq!(ghost.pos_at(ghost.ticks() + 100), game.player_pos());
+    assert_eq!(ghost.pos_at(0), glm::Vec2::new(-0.5, 0.));
+}
+
+#[test]
+fn splits_say_how_far_ahead_or_behind() {
+    let ghost = Ghost::from_replay(&win(0));
+    // Where the ghost was after one second, a second late
+    let pos = ghost.pos_at(TICK_RATE);
+    assert!((ghost.split(TICK_RATE * 2, pos) - 1.).abs() < 1e-6);
+    // ...and a second early
+    let pos = ghost.pos_at(TICK_RATE * 2);
+    assert!((ghost.split(TICK_RATE, pos) + 1.).abs() < 1e-6);
+}
+
+#[test]
+fn only_faster_wins_replace_the_best() {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("r_circlegauntlet-ghosts-{}", std::process::id()));
+    let best_runs = BestRuns::new(&dir);
+    assert_eq!(best_runs.best(&runway()), None);
+
+    let slow = win(TICK_RATE);
+    let fast = win(0);
+    assert!(best_runs.offer(&slow).unwrap());
+    assert!(best_runs.offer(&fast).unwrap());
+    assert!(!best_runs.offer(&slow).unwrap());
+    assert_eq!(best_runs.best(&runway()), Some(fast));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn each_layout_has_its_own_best() {
+    let best_runs = BestRuns::new("ghosts");
+    let mut moved = runway();
+    moved.goals[0].pos = [0.5, 0.25];
+    assert_ne!(best_runs.path(&runway()), best_runs.path(&moved));
+    assert_eq!(best_runs.path(&runway()), best_runs.path(&runway()));
+}
@@ -0,0 +1,170 @@
+use r_circlegauntlet::hud::{self, Align, Label};
+use r_circlegauntlet::score::Score;
+use r_circlegauntlet::*;
+
+fn label(text: &str, align: Align) -> Label {
+    Label::new(text, glm::Vec2::new(0., 0.), 1., align)
+}
+
+/// The leftmost and rightmost edges of everything a label fills in
+fn extent(label: &Label) -> (f32, f32) {
+    label
+        .blocks()
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(left, right), block| {
+            (
+                left.min(block.center[0] - block.half_size[0]),
+                right.max(block.center[0] + block.half_size[0]),
+            )
+        })
+}
+
+#[test]
+fn text_is_five_by_seven_with_a_gap_between_characters() {
+    assert_eq!(label("", Align::Left).width(), 0.);
+    assert_eq!(label("A", Align::Left).width(), 5.);
+    assert_eq!(label("AB", Align::Left).width(), 11.);
+    assert_eq!(label("A", Align::Left).height(), 7.);
+}
+
+#[test]
+fn lit_pixels_in_a_row_become_one_block() {
+    // The top row of a T is one block, and its stem is one per row below that
+    let blocks = label("T", Align::Left).blocks();
+    assert_eq!(blocks.len(), 7);
+    assert_eq!(blocks[0].center, glm::Vec2::new(2.5, -0.5));
+    assert_eq!(blocks[0].half_size, glm::Vec2::new(2.5, 0.5));
+    assert_eq!(blocks[6].center, glm::Vec2::new(2.5, -6.5));
+    assert_eq!(blocks[6].half_size, glm::Vec2::new(0.5, 0.5));
+    // Spaces take up room but don't draw anything
+    assert_eq!(label(" ", Align::Left).blocks(), vec![]);
+}
+
+#[test]
+fn labels_line_up_the_way_they_say() {
+    assert_eq!(extent(&label("HE", Align::Left)), (0., 11.));
+    assert_eq!(extent(&label("HE", Align::Right)), (-11., 0.));
+    assert_eq!(extent(&label("HE", Align::Center)), (-5.5, 5.5));
+}
+
+#[test]
+fn lowercase_is_drawn_in_capitals_and_the_unknown_as_question_marks() {
+    assert_eq!(
+        label("abc", Align::Left).blocks(),
+        label("ABC", Align::Left).blocks()
+    );
+    assert_eq!(
+        label("\u{263a}", Align::Left).blocks(),
+        label("?", Align::Left).blocks()
+    );
+}
+
+fn texts(labels: &[Label]) -> Vec<&str> {
+    labels.iter().map(|label| label.text.as_str()).collect()
+}
+
+#[test]
+fn the_hud_shows_the_state_of_the_game() {
+    let game = Game::new(GameConfig {
+        seed: 42,
+        ..GameConfig::default()
+    });
+    let labels = hud::labels(&game, State::Playing);
+    let texts = texts(&labels);
+    assert!(texts.contains(&"10"), "{:?}", texts);
+    assert!(texts.contains(&"Seed 42"), "{:?}", texts);
+    assert!(texts.contains(&"0.00"), "{:?}", texts);
+    assert!(texts.contains(&"SEED 42"), "{:?}", texts);
+    assert!(texts.iter().any(|text| text.starts_with("SCORE ")));
+    assert_eq!(hud::life_icons(&game).len(), LIFE_MAX as usize);
+    // Nothing drawn outside the arena
+    for label in &labels {
+        let (left, right) = extent(label);
+        assert!(left >= -1. && right <= 1., "{:?}", label);
+    }
+}
+
+#[test]
+fn banners_replace_the_console_messages() {
+    let game = Game::new(GameConfig::default());
+    let playing = hud::labels(&game, State::Playing).len();
+    for (state, title) in [
+        (State::Won, "YOU WIN!"),
+        (State::Died, "YOU DIED!"),
+        (State::Paused, "PAUSED"),
+        (State::Title, "CIRCLE GAUNTLET"),
+    ] {
+        let labels = hud::labels(&game, state);
+        let banner = &labels[playing];
+        assert_eq!(banner.text, title);
+        assert_eq!(banner.align, Align::Center);
+        assert_eq!(banner.pos[0], 0.);
+        // Even the long ones fit across the arena
+        for label in &labels[playing..] {
+            let (left, right) = extent(label);
+            assert!(left >= -1. && right <= 1., "{:?}", label);
+        }
+    }
+}
+
+#[test]
+fn the_win_screen_lists_the_best_scores() {
+    let game = Game::new(GameConfig::default());
+    let won = hud::labels(&game, State::Won);
+    let table: Vec<Score> = (0..10)
+        .map(|i| Score {
+            points: 5000 - i * 100,
+            ..Score::new(10., 5, 0, Difficulty::Nightmare)
+        })
+        .collect();
+
+    let labels = hud::high_scores(&table, Some(7));
+    let rows = texts(&labels);
+    assert_eq!(rows[0], "#8 ON THIS LAYOUT");
+    // The top five, then this run's row
+    assert_eq!(labels.len(), 7);
+    assert!(rows[1].starts_with(" 1.   5000"), "{:?}", rows);
+    assert!(rows[6].starts_with(" 8.   4300"), "{:?}", rows);
+    assert_eq!(labels[6].tint, Tint::GOAL);
+    assert!(labels[1..6].iter().all(|label| label.tint == Tint::TEXT));
+    // Every row is the same width, so the columns line up
+    assert!(labels[1..]
+        .iter()
+        .all(|label| label.width() == labels[1].width()));
+
+    // Under the banner, clear of the seed and score along the bottom, and inside the arena
+    let banner_bottom = won.last().unwrap().pos[1] - won.last().unwrap().height();
+    let hud_bottom = won[3].pos[1];
+    for label in &labels {
+        assert!(label.pos[1] < banner_bottom, "{:?}", label);
+        assert!(label.pos[1] - label.height() > hud_bottom, "{:?}", label);
+        let (left, right) = extent(label);
+        assert!(left >= -1. && right <= 1., "{:?}", label);
+    }
+
+    let labels = hud::high_scores(&table[..3], None);
+    assert_eq!(texts(&labels)[0], "NOT IN THE TOP 10 ON THIS LAYOUT");
+    assert_eq!(labels.len(), 4);
+}
+
+#[test]
+fn every_win_shares_one_screen() {
+    let game = Game::new(GameConfig::default());
+    assert_eq!(
+        hud::win_screen(&game, hud::PLAY_AGAIN, None),
+        hud::labels(&game, State::Won)
+    );
+
+    // Moving on through a campaign only changes what it says to do next
+    let table = vec![Score::new(10., 5, 0, Difficulty::Normal)];
+    let free_play = hud::win_screen(&game, hud::PLAY_AGAIN, Some((&table, Some(0))));
+    let campaign = hud::win_screen(&game, hud::CARRY_ON, Some((&table, Some(0))));
+    assert_eq!(campaign.len(), free_play.len());
+    let differences: Vec<(&str, &str)> = texts(&free_play)
+        .into_iter()
+        .zip(texts(&campaign))
+        .filter(|(free_play, campaign)| free_play != campaign)
+        .collect();
+    assert_eq!(differences, [(hud::PLAY_AGAIN, hud::CARRY_ON)]);
+    assert!(texts(&campaign).contains(&"#1 ON THIS LAYOUT"));
+}
@@ -0,0 +1,122 @@
+use r_circlegauntlet::*;
+
+fn level(text: &str) -> Level {
+    Level::parse(text).unwrap()
+}
+
+fn hits(events: &[GameEvent]) -> usize {
+    events
+        .iter()
+        .filter(|event| matches!(event, GameEvent::Hit { .. }))
+        .count()
+}
+
+fn run(game: &mut Game, input: &Input, seconds: f32) -> Vec<GameEvent> {
+    let mut events = vec![];
+    for _ in 0..(seconds * TICK_RATE as f32) as u32 {
+        events.extend(game.step(input, TICK));
+    }
+    events
+}
+
+#[test]
+fn grazing_an_obstacle_costs_one_life() {
+    // Slide past an obstacle just close enough to scrape it
+    let level = level(
+        r#"
+name = "Graze"
+player_start = [-0.5, 0.14]
+
+[[goal]]
+pos = [0.9, -0.9]
+
+[[obstacle]]
+pos = [0.0, 0.0]
+"#,
+    );
+    // Even with no invulnerability window at all, one contact is one life
+    let config = GameConfig {
+        invulnerability: 0.,
+        ..GameConfig::default()
+    };
+    let mut game = Game::with_level(config, level);
+    let input = Input {
+        direction: glm::Vec2::new(1.0, 0.0),
+    };
+    let events = run(&mut game, &input, 1.5);
+    assert_eq!(hits(&events), 1);
+    assert_eq!(game.life(), LIFE_MAX - 1);
+}
+
+#[test]
+fn starting_on_top_of_an_obstacle_costs_one_life() {
+    let level = level(
+        r#"
+name = "Stuck"
+player_start = [0.0, 0.0]
+
+[[goal]]
+pos = [0.9, -0.9]
+
+[[obstacle]]
+pos = [0.05, 0.0]
+"#,
+    );
+    let mut game = Game::with_level(GameConfig::default(), level);
+    let events = run(&mut game, &Input::default(), 0.5);
+    assert_eq!(hits(&events), 1);
+}
+
+#[test]
+fn a_pinning_enemy_cannot_drain_life_during_the_window() {
+    // The enemy starts right on top of the player and keeps coming back for more
+    let level = level(
+        r#"
+name = "Pinned"
+player_start = [0.0, 0.0]
+
+[[goal]]
+pos = [0.9, -0.9]
+
+[[enemy]]
+spawn = [0.05, 0.0]
+"#,
+    );
+    let config = GameConfig {
+        invulnerability: 1.0,
+        ..GameConfig::default()
+    };
+    let mut game = Game::with_level(config, level);
+    let events = run(&mut game, &Input::default(), 1.0);
+    assert_eq!(hits(&events), 1);
+    assert!(game.invulnerable() > 0.);
+
+    // After the window closes the enemy can hurt again, but never more than once per window
+    let events = run(&mut game, &Input::default(), 3.0);
+    assert!(hits(&events) <= 3, "{:?}", events);
+}
+
+#[test]
+fn invulnerability_wears_off() {
+    let level = level(
+        r#"
+name = "Stuck"
+player_start = [0.0, 0.0]
+
+[[goal]]
+pos = [0.9, -0.9]
+
+[[obstacle]]
+pos = [0.05, 0.0]
+"#,
+    );
+    let config = GameConfig {
+        invulnerability: 0.5,
+        ..GameConfig::default()
+    };
+    let mut game = Game::with_level(config, level);
+    game.step(&Input::default(), TICK);
+    assert_eq!(game.invulnerable(), 0.5);
+    run(&mut game, &Input::default(), 0.6);
+    assert_eq!(game.invulnerable(), 0.);
+}
@@ -0,0 +1,147 @@
+use r_circlegauntlet::*;
+use std::fs;
+
+#[test]
+fn bundled_levels_load() {
+    let campaign = Campaign::load("levels/campaign.toml").unwrap();
+    for path in &campaign.levels {
+        let level = Level::load(path).unwrap_or_else(|err| panic!("{}", err));
+        Game::with_level(GameConfig::default(), level);
+    }
+
+    // Every bundled level should be part of the campaign
+    for entry in fs::read_dir("levels").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "toml") && !path.ends_with("campaign.toml") {
+            assert!(campaign.levels.contains(&path), "{:?}", path);
+        }
+    }
+}
+
+#[test]
+fn defaults_fill_in_radii() {
+    let level = Level::parse(
+        r#"
+name = "Tiny"
+player_start = [-0.5, 0.5]
+
+[[goal]]
+pos = [0.5, -0.5]
+
+[[obstacle]]
+pos = [0.0, 0.0]
+
+[[enemy]]
+spawn = [0.5, 0.5]
+"#,
+    )
+    .unwrap();
+    assert_eq!(level.goals[0].radius, GOAL_RADIUS);
+    assert_eq!(level.obstacles[0].radius, OBSTACLE_RADIUS);
+    assert_eq!(level.player_radius, PLAYER_RADIUS);
+    assert_eq!(level.enemies[0].radius, ENEMY_WIDTH * 0.5);
+}
+
+#[test]
+fn parse_errors_point_at_the_line() {
+    let err = Level::parse(
+        r#"name = "Broken"
+player_start = [-0.5, 0.5]
+
+[[goal]]
+pos = [0.5, "oops"]
+"#,
+    )
+    .unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("line 5"), "{}", message);
+
+    let err = Level::parse(
+        r#"name = "Typo"
+player_start = [-0.5, 0.5]
+[[goal]]
+pos = [0.5, -0.5]
+radios = 0.1
+"#,
+    )
+    .unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("line 5"), "{}", message);
+    assert!(message.contains("radios"), "{}", message);
+}
+
+#[test]
+fn invalid_values_name_the_field() {
+    let err = Level::parse(
+        r#"name = "Negative"
+player_start = [-0.5, 0.5]
+
+[[goal]]
+pos = [0.5, -0.5]
+
+[[obstacle]]
+pos = [0.0, 0.0]
+
+[[obstacle]]
+pos = [0.2, 0.0]
+radius = -1.0
+"#,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "obstacle[1].radius: must be positive");
+
+    let err = Level::parse(
+        r#"name = "Nowhere to go"
+player_start = [-0.5, 0.5]
+goal = []
+"#,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "goal: a level needs at least one goal");
+}
+
+#[test]
+fn load_reports_the_path() {
+    let err = Level::load("levels/does_not_exist.toml").unwrap_err();
+    assert!(err.to_string().starts_with("levels/does_not_exist.toml: "));
+}
+
+#[test]
+fn reaching_any_goal_wins() {
+    let level = Level::parse(
+        r#"
+name = "Two goals"
+player_start = [0.0, 0.0]
+
+[[goal]]
+pos = [0.9, 0.9]
+
+[[goal]]
+pos = [-0.3, 0.0]
+"#,
+    )
+    .unwrap();
+    let mut game = Game::with_level(GameConfig::default(), level);
+    let input = Input {
+        direction: glm::Vec2::new(-1.0, 0.0),
+    };
+    let mut events = vec![];
+    for _ in 0..TICK_RATE * 5 {
+        events.extend(game.step(&input, TICK));
+    }
+    assert_eq!(events, vec![GameEvent::Won]);
+}
+
+#[test]
+fn keys_tell_layouts_apart() {
+    let level = Level::load("levels/01_first_steps.toml").unwrap();
+    let key = level.key();
+    assert!(key.starts_with("first_steps-"), "{}", key);
+    assert_eq!(
+        Level::load("levels/01_first_steps.toml").unwrap().key(),
+        key
+    );
+    let mut moved = level.clone();
+    moved.player_start[0] += 0.01;
+    assert_ne!(moved.key(), key);
+}
@@ -0,0 +1,147 @@
+use r_circlegauntlet::glm::Vec2;
+use r_circlegauntlet::level::{Motion, ObstacleSpec, Pulse};
+use r_circlegauntlet::motion::Animation;
+use r_circlegauntlet::*;
+
+const EPSILON: f32 = 1e-4;
+const DT: f32 = 1. / 60.;
+
+fn obstacle(motion: Option<Motion>, pulse: Option<Pulse>) -> ObstacleSpec {
+    ObstacleSpec {
+        pos: [0.5, 0.],
+        radius: 0.1,
+        motion,
+        pulse,
+    }
+}
+
+/// Run an animation for `seconds`, returning every position and radius along the way
+fn run(spec: &ObstacleSpec, seconds: f32) -> Vec<(Vec2, f32)> {
+    let animation = Animation::from_spec(spec).unwrap();
+    let mut pos = Vec2::from(spec.pos);
+    let mut vel = animation.initial_velocity();
+    let mut radius = spec.radius;
+    let steps = (seconds / DT).round() as usize;
+    (1..=steps)
+        .map(|step| {
+            animation.update(step as f32 * DT, DT, &mut pos, &mut vel, &mut radius);
+            (pos, radius)
+        })
+        .collect()
+}
+
+#[test]
+fn still_obstacles_have_no_animation() {
+    assert_eq!(Animation::from_spec(&obstacle(None, None)), None);
+}
+
+#[test]
+fn ping_pong_gets_there_and_back() {
+    let spec = obstacle(
+        Some(Motion::PingPong {
+            to: [-0.5, 0.],
+            period: 2.,
+        }),
+        None,
+    );
+    let frames = run(&spec, 2.);
+    let (halfway, _) = frames[frames.len() / 2 - 1];
+    assert!((halfway - Vec2::new(-0.5, 0.)).magnitude() < EPSILON);
+    let (end, _) = frames[frames.len() - 1];
+    assert!((end - Vec2::new(0.5, 0.)).magnitude() < EPSILON);
+}
+
+#[test]
+fn orbit_keeps_its_distance() {
+    let spec = obstacle(
+        Some(Motion::Orbit {
+            center: [0., 0.],
+            period: 3.,
+        }),
+        None,
+    );
+    for (pos, _) in run(&spec, 3.) {
+        assert!((pos.magnitude() - 0.5).abs() < EPSILON);
+    }
+}
+
+#[test]
+fn bounce_stays_in_the_arena() {
+    let spec = obstacle(
+        Some(Motion::Bounce {
+            velocity: [1.3, 0.7],
+        }),
+        None,
+    );
+    for (pos, radius) in run(&spec, 10.) {
+        assert!(pos[0].abs() <= 1. - radius + EPSILON, "{:?}", pos);
+        assert!(pos[1].abs() <= 1. - radius + EPSILON, "{:?}", pos);
+    }
+}
+
+#[test]
+fn pulse_swings_between_the_two_sizes() {
+    let spec = obstacle(
+        None,
+        Some(Pulse {
+            radius: 0.2,
+            period: 1.,
+        }),
+    );
+    let radii: Vec<f32> = run(&spec, 1.).into_iter().map(|(_, r)| r).collect();
+    let smallest = radii.iter().copied().fold(f32::INFINITY, f32::min);
+    let biggest = radii.iter().copied().fold(0., f32::max);
+    assert!((smallest - 0.1).abs() < EPSILON);
+    assert!((biggest - 0.2).abs() < EPSILON);
+}
+
+#[test]
+fn motion_is_checked_when_loading() {
+    let err = Level::parse(
+        r#"name = "Frozen"
+player_start = [-0.5, 0.5]
+
+[[goal]]
+pos = [0.5, -0.5]
+
+[[obstacle]]
+pos = [0.0, 0.0]
+motion = { type = "ping_pong", to = [0.5, 0.0], period = 0.0 }
+"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "obstacle[0].motion.period: must be positive"
+    );
+}
+
+#[test]
+fn a_moving_obstacle_hits_a_player_standing_still() {
+    // Sweeping down across the player's start, much too fast to see in a single step
+    let level = Level::parse(
+        r#"name = "Sweeper"
+player_start = [0.0, 0.0]
+
+[[goal]]
+pos = [0.8, 0.8]
+
+[[obstacle]]
+pos = [0.0, 0.6]
+radius = 0.05
+motion = { type = "ping_pong", to = [0.0, -0.6], period = 0.5 }
+"#,
+    )
+    .unwrap();
+    let mut game = Game::with_level(GameConfig::default(), level);
+    let mut hit = false;
+    for _ in 0..30 {
+        hit |= game
+            .step(&Input::default(), 1. / 30.)
+            .iter()
+            .any(|event| matches!(event, GameEvent::Hit { .. }));
+    }
+    assert!(hit);
+    // Knocked out of the way rather than left where it was
+    assert!(game.player_pos().magnitude() > PLAYER_RADIUS);
+}
@@ -0,0 +1,143 @@
+use r_circlegauntlet::glm::{self, Vec2};
+use r_circlegauntlet::level::Navigation;
+use r_circlegauntlet::navigation::NavGrid;
+use r_circlegauntlet::*;
+
+/// A wall of obstacles down the middle, with a way around at the top and bottom, and an enemy on
+/// the far side of it from the player
+fn walled_level(navigation: &str) -> Level {
+    let mut text = format!(
+        r#"
+name = "Wall"
+player_start = [-0.6, 0.0]
+
+[[goal]]
+pos = [-0.9, 0.9]
+
+[[enemy]]
+type = "chaser"
+spawn = [0.6, 0.0]
+navigation = "{}"
+"#,
+        navigation
+    );
+    for i in -6..=6 {
+        text.push_str(&format!(
+            "\n[[obstacle]]\npos = [0.0, {}]\n",
+            i as f32 * 0.1
+        ));
+    }
+    Level::parse(&text).unwrap()
+}
+
+fn wall() -> Vec<(Vec2, f32)> {
+    (-6..=6)
+        .map(|i| (Vec2::new(0., i as f32 * 0.1), OBSTACLE_RADIUS))
+        .collect()
+}
+
+fn enemy_pos(game: &Game) -> Position {
+    game.enemy_positions()[0]
+}
+
+fn inside_wall(pos: Position) -> bool {
+    wall().iter().any(|&(obstacle, radius)| {
+        glm::distance(&pos, &obstacle) < radius + ENEMY_WIDTH * 0.5 - 1e-4
+    })
+}
+
+#[test]
+fn routes_go_around_obstacles() {
+    let grid = NavGrid::new(&wall(), ENEMY_WIDTH * 0.5);
+    assert!(grid.is_blocked(Vec2::zeros()));
+    let route = grid.route(Vec2::new(0.6, 0.), Vec2::new(-0.6, 0.)).unwrap();
+    let centers: Vec<Vec2> = route
+        .iter()
+        .map(|&(x, y)| Vec2::new(-1. + (x as f32 + 0.5) / 32., -1. + (y as f32 + 0.5) / 32.))
+        .collect();
+    for &center in &centers[1..centers.len() - 1] {
+        assert!(!grid.is_blocked(center), "route goes through {:?}", center);
+    }
+    // Around the top or bottom of the wall, not through it
+    assert!(centers.iter().any(|center| center[1].abs() > 0.6));
+}
+
+#[test]
+fn with_no_way_through_steer_straight_at_the_target() {
+    // A ring of obstacles the enemy can't get into
+    let ring: Vec<(Vec2, f32)> = (0..24)
+        .map(|i| {
+            let angle = i as f32 / 24. * std::f32::consts::TAU;
+            (Vec2::new(angle.cos(), angle.sin()) * 0.4, 0.06)
+        })
+        .collect();
+    let grid = NavGrid::new(&ring, ENEMY_WIDTH * 0.5);
+    assert_eq!(grid.route(Vec2::new(0.8, 0.8), Vec2::zeros()), None);
+    assert_eq!(
+        grid.steer(Vec2::new(0.8, 0.8), Vec2::zeros()),
+        Vec2::zeros()
+    );
+}
+
+#[test]
+fn pathfinding_enemies_find_their_way_around() {
+    let mut game = Game::with_level(GameConfig::default(), walled_level("pathfind"));
+    let mut hit = false;
+    for _ in 0..TICK_RATE * 15 {
+        hit |= game
+            .step(&Input::default(), TICK)
+            .iter()
+            .any(|event| matches!(event, GameEvent::Hit { .. }));
+        assert!(!inside_wall(enemy_pos(&game)));
+        if hit {
+            break;
+        }
+    }
+    assert!(
+        hit,
+        "never reached the player, stuck at {:?}",
+        enemy_pos(&game)
+    );
+}
+
+#[test]
+fn bouncing_enemies_stay_out_of_obstacles() {
+    let mut game = Game::with_level(GameConfig::default(), walled_level("bounce"));
+    for _ in 0..TICK_RATE * 5 {
+        game.step(&Input::default(), TICK);
+        assert!(!inside_wall(enemy_pos(&game)));
+    }
+}
+
+#[test]
+fn ghosts_pass_through_obstacles() {
+    let mut game = Game::with_level(GameConfig::default(), walled_level("ghost"));
+    let mut passed_through = false;
+    for _ in 0..TICK_RATE * 5 {
+        game.step(&Input::default(), TICK);
+        passed_through |= inside_wall(enemy_pos(&game));
+    }
+    assert!(passed_through);
+}
+
+#[test]
+fn enemies_bounce_off_obstacles_unless_told_otherwise() {
+    assert_eq!(
+        walled_level("bounce").enemies[0].navigation,
+        Navigation::Bounce
+    );
+    let level = Level::parse(
+        r#"
+name = "Default"
+player_start = [0.0, 0.0]
+
+[[goal]]
+pos = [0.5, 0.5]
+
+[[enemy]]
+spawn = [-0.5, -0.5]
+"#,
+    )
+    .unwrap();
+    assert_eq!(level.enemies[0].navigation, Navigation::Bounce);
+}
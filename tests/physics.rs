@@ -0,0 +1,177 @@
+use r_circlegauntlet::physics::PhysicsWatcher;
+use r_circlegauntlet::FileError;
+use r_circlegauntlet::*;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+#[test]
+fn the_bundled_file_matches_the_defaults() {
+    assert_eq!(
+        PhysicsConfig::load("physics.toml").unwrap(),
+        PhysicsConfig::default()
+    );
+}
+
+#[test]
+fn missing_values_use_the_defaults() {
+    let physics = PhysicsConfig::parse("max_speed = 0.75").unwrap();
+    assert_eq!(
+        physics,
+        PhysicsConfig {
+            max_speed: 0.75,
+            ..PhysicsConfig::default()
+        }
+    );
+}
+
+#[test]
+fn bad_values_name_the_field() {
+    let err = PhysicsConfig::parse("drag = -1.0").unwrap_err();
+    assert_eq!(err.to_string(), "drag: must be zero or more");
+    let err = PhysicsConfig::parse("enemy_max_speed = 0.0").unwrap_err();
+    assert_eq!(err.to_string(), "enemy_max_speed: must be positive");
+    let err = PhysicsConfig::parse("restitution = 1.5").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "restitution: must be at most 1.0, or bounces would speed things up"
+    );
+    assert!(PhysicsConfig::parse("max_sped = 0.5").is_err());
+}
+
+#[test]
+fn drag_too_strong_to_be_stable_is_rejected() {
+    // Drag takes `drag * TICK` of the speed away each tick, so at the tick rate it would stop
+    // things dead, and past it turn them around
+    let limit = TICK_RATE as f32;
+    assert!(PhysicsConfig::parse(&format!("drag = {}", limit - 1.)).is_ok());
+    let err = PhysicsConfig::parse(&format!("drag = {}", limit)).unwrap_err();
+    assert!(matches!(&err, FileError::Invalid { field, .. } if field == "drag"));
+    assert_eq!(
+        err.to_string(),
+        "drag: must be less than 120, or things would turn around every tick"
+    );
+    let err = PhysicsConfig::parse(&format!("enemy_drag = {}", limit * 2.)).unwrap_err();
+    assert!(err.to_string().starts_with("enemy_drag: "));
+}
+
+/// Write `text` to `path`, making sure it looks changed even on filesystems with coarse timestamps
+fn write(path: &Path, text: &str, age: u64) {
+    fs::write(path, text).unwrap();
+    let modified = SystemTime::now() - Duration::from_secs(age);
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+}
+
+#[test]
+fn the_watcher_reloads_edits_and_reports_bad_ones() {
+    let path: PathBuf = std::env::temp_dir()
+        .join(format!("r_circlegauntlet-physics-{}", std::process::id()))
+        .join("physics.toml");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    write(&path, "max_speed = 0.5", 30);
+
+    let mut watcher = PhysicsWatcher::new(&path);
+    assert!(watcher.poll().is_none());
+
+    write(&path, "max_speed = 1.0", 20);
+    let physics = watcher.poll().unwrap().unwrap();
+    assert_eq!(physics.max_speed, 1.0);
+    assert!(watcher.poll().is_none());
+
+    write(&path, "max_speed = -1.0", 10);
+    let err = watcher.poll().unwrap().unwrap_err();
+    assert!(err.to_string().ends_with("max_speed: must be zero or more"));
+    assert!(watcher.poll().is_none());
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+/// How fast the player is going after holding right for a few seconds in an empty arena
+fn top_speed(physics: PhysicsConfig) -> f32 {
+    let level = Level::parse(
+        r#"name = "Runway"
+player_start = [-0.9, 0.0]
+
+[[goal]]
+pos = [0.8, 0.8]
+"#,
+    )
+    .unwrap();
+    let mut session = Session::new(GameConfig::default(), Some(level));
+    session.set_physics(physics);
+    session.start();
+    let input = Input {
+        direction: glm::Vec2::new(1., 0.),
+    };
+    let before = session.game().player_pos();
+    for _ in 0..TICK_RATE {
+        session.step(&input, TICK);
+    }
+    (session.game().player_pos() - before).magnitude()
+}
+
+#[test]
+fn physics_changes_how_the_player_moves() {
+    let slow = top_speed(PhysicsConfig {
+        max_speed: 0.1,
+        ..PhysicsConfig::default()
+    });
+    let normal = top_speed(PhysicsConfig::default());
+    let sluggish = top_speed(PhysicsConfig {
+        acceleration: 0.25,
+        ..PhysicsConfig::default()
+    });
+    assert!(slow < normal, "{} vs {}", slow, normal);
+    assert!(sluggish < normal, "{} vs {}", sluggish, normal);
+}
+
+/// How far the player bounces back after coasting into an obstacle dead ahead
+fn rebound(physics: PhysicsConfig) -> f32 {
+    let level = Level::parse(
+        r#"name = "Backboard"
+player_start = [-0.25, 0.0]
+
+[[goal]]
+pos = [0.8, 0.8]
+
+[[obstacle]]
+pos = [0.0, 0.0]
+"#,
+    )
+    .unwrap();
+    let mut session = Session::new(GameConfig::default(), Some(level));
+    session.set_physics(physics);
+    session.start();
+    let push = Input {
+        direction: glm::Vec2::new(1., 0.),
+    };
+    // Get up to speed, then let go before reaching the obstacle
+    for _ in 0..TICK_RATE / 4 {
+        session.step(&push, TICK);
+    }
+    let mut furthest = f32::MIN;
+    for _ in 0..TICK_RATE {
+        session.step(&Input::default(), TICK);
+        furthest = furthest.max(session.game().player_pos()[0]);
+    }
+    furthest - session.game().player_pos()[0]
+}
+
+#[test]
+fn lower_restitution_rebounds_slower() {
+    let lively = rebound(PhysicsConfig {
+        restitution: 1.,
+        ..PhysicsConfig::default()
+    });
+    let dead = rebound(PhysicsConfig {
+        restitution: 0.25,
+        ..PhysicsConfig::default()
+    });
+    assert!(dead > 0., "{} vs {}", dead, lively);
+    assert!(dead < lively * 0.5, "{} vs {}", dead, lively);
+}
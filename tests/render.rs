@@ -0,0 +1,143 @@
+use r_circlegauntlet::hud::{self, Align, Label};
+use r_circlegauntlet::render::{Ascii, Canvas, Renderer, Scene};
+use r_circlegauntlet::*;
+use std::path::{Path, PathBuf};
+
+/// Snapshots to compare against are kept here.  Run with `UPDATE_GOLDEN=1` to write them afresh
+/// after changing how things look, then check the new ones over before committing them.
+const GOLDEN_DIR: &str = "tests/golden";
+
+fn updating() -> bool {
+    std::env::var_os("UPDATE_GOLDEN").is_some()
+}
+
+fn golden(name: &str) -> PathBuf {
+    Path::new(GOLDEN_DIR).join(name)
+}
+
+/// Each bundled level, as it is before anything has moved
+fn bundled() -> Vec<(String, Game)> {
+    let campaign = Campaign::load("levels/campaign.toml").unwrap();
+    campaign
+        .levels
+        .iter()
+        .map(|path| {
+            let name = Path::new(path).file_stem().unwrap().to_string_lossy();
+            let level = Level::load(path).unwrap();
+            (
+                name.into_owned(),
+                Game::with_level(GameConfig::default(), level),
+            )
+        })
+        .collect()
+}
+
+fn ascii(scene: &Scene) -> String {
+    let mut ascii = Ascii::new(64, 32);
+    scene.draw(&mut ascii);
+    ascii.to_string()
+}
+
+#[test]
+fn level_layouts_match_their_ascii_snapshots() {
+    for (name, game) in bundled() {
+        let path = golden(&format!("{}.txt", name));
+        let drawn = ascii(&Scene::new(&game));
+        if updating() {
+            std::fs::create_dir_all(GOLDEN_DIR).unwrap();
+            std::fs::write(&path, &drawn).unwrap();
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        assert_eq!(drawn, expected, "{} has changed", path.display());
+    }
+}
+
+/// Decode a PNG into its size and red, green and blue bytes
+fn decode(png: &[u8]) -> (u32, u32, Vec<u8>) {
+    let mut reader = png::Decoder::new(png).read_info().unwrap();
+    let mut rgb = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut rgb).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgb);
+    rgb.truncate(info.buffer_size());
+    (info.width, info.height, rgb)
+}
+
+#[test]
+fn the_title_screen_matches_its_png_snapshot() {
+    let (_, game) = bundled().remove(0);
+    let labels = hud::labels(&game, State::Title);
+    let scene = Scene {
+        labels: &labels,
+        ..Scene::new(&game)
+    };
+    let mut canvas = Canvas::new(256, 256);
+    scene.draw(&mut canvas);
+
+    let path = golden("title.png");
+    if updating() {
+        canvas.save_png(&path).unwrap();
+    }
+    let expected = std::fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+    // Compare pixels rather than bytes, which depend on how the encoder compresses them
+    assert_eq!(
+        decode(&canvas.to_png()),
+        decode(&expected),
+        "{} has changed",
+        path.display()
+    );
+}
+
+#[test]
+fn canvases_put_things_where_they_are_in_the_arena() {
+    let mut canvas = Canvas::new(100, 50);
+    canvas.start();
+    canvas.circle(glm::Vec2::new(0.5, 0.5), 0.1, Tint::GOAL);
+    // Up is the top of the image
+    assert_eq!(canvas.pixel(75, 12), [0, 255, 0]);
+    assert_eq!(canvas.pixel(75, 37), [0, 0, 0]);
+    assert_eq!(canvas.pixel(25, 12), [0, 0, 0]);
+
+    let wall = Rectangle {
+        half_size: glm::Vec2::new(0.5, 0.05),
+        angle: std::f32::consts::FRAC_PI_2,
+    };
+    canvas.rectangle(glm::Vec2::new(0., 0.), wall, Tint::OBSTACLE);
+    // Turned a quarter, so it stands upright
+    assert_eq!(canvas.pixel(50, 25), [255, 0, 0]);
+    assert_eq!(canvas.pixel(50, 15), [255, 0, 0]);
+    assert_eq!(canvas.pixel(65, 25), [0, 0, 0]);
+
+    // Translucent circles mix with whatever they're drawn over
+    canvas.translucent_circle(glm::Vec2::new(-0.5, 0.), 0.4, Tint::PLAYER, 0.4);
+    assert_eq!(canvas.pixel(25, 25), [0, 0, 102]);
+    canvas.translucent_circle(glm::Vec2::new(0., 0.), 0.2, Tint::PLAYER, 0.5);
+    assert_eq!(canvas.pixel(50, 25), [128, 0, 128]);
+    assert_eq!(canvas.pixel(45, 25), [0, 0, 128]);
+
+    // Starting a new frame clears the last one
+    canvas.start();
+    assert_eq!(canvas.rgb(), vec![0; 100 * 50 * 3]);
+}
+
+#[test]
+fn ascii_draws_each_kind_of_thing_with_its_own_character() {
+    let mut ascii = Ascii::new(8, 4);
+    ascii.start();
+    ascii.circle(glm::Vec2::new(-0.875, 0.75), 0.1, Tint::PLAYER);
+    ascii.circle(glm::Vec2::new(0.375, 0.25), 0.1, Tint::GOAL);
+    ascii.label(&Label::new(
+        "-",
+        glm::Vec2::new(0.75, -0.4),
+        0.1,
+        Align::Center,
+    ));
+    ascii.circle(glm::Vec2::new(-0.375, -0.75), 0.1, Tint([0.5, 0.5, 0.5]));
+    assert_eq!(
+        ascii.to_string(),
+        "P.......\n\
+         .....G..\n\
+         ........\n\
+         ..?...**\n"
+    );
+}
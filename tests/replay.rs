@@ -0,0 +1,199 @@
+use legion::prelude::*;
+use r_circlegauntlet::replay::{self, Span, REPLAY_VERSION};
+use r_circlegauntlet::*;
+use std::path::{Path, PathBuf};
+
+/// Input that wanders around: straight, diagonal and idle stretches of different lengths
+fn wandering_input(tick: u32) -> Input {
+    let directions = [
+        glm::Vec2::new(1., 0.),
+        glm::Vec2::new(1., 1.).normalize(),
+        glm::Vec2::zeros(),
+        glm::Vec2::new(-0.3, 0.8).normalize(),
+        glm::Vec2::new(0., -1.),
+    ];
+    Input {
+        direction: directions[(tick / 37 + tick / 101) as usize % directions.len()],
+    }
+}
+
+/// Everything about how a game ended up, down to the bit
+fn fingerprint(game: &Game) -> Vec<u32> {
+    let mut bits = vec![game.life() as u32, game.is_over() as u32];
+    for pos in <Read<Position>>::query().iter(game.world()) {
+        bits.push(pos[0].to_bits());
+        bits.push(pos[1].to_bits());
+    }
+    bits
+}
+
+/// A session that has played a while on a busy layout, including a change of physics partway
+fn recorded_session() -> (Session, Vec<GameEvent>) {
+    let config = Difficulty::Hard.apply(GameConfig {
+        seed: 11,
+        ..GameConfig::default()
+    });
+    let config = GameConfig {
+        life_max: 1000,
+        ..config
+    };
+    let mut session = Session::new(config, None);
+    session.start();
+    let mut events = vec![];
+    for tick in 0..TICK_RATE * 8 {
+        if tick == TICK_RATE * 3 {
+            session.set_physics(PhysicsConfig {
+                drag: 0.3,
+                restitution: 0.5,
+                ..PhysicsConfig::default()
+            });
+        }
+        events.extend(session.step(&wandering_input(tick), TICK));
+    }
+    (session, events)
+}
+
+#[test]
+fn held_input_is_stored_as_one_span() {
+    let mut session = Session::new(GameConfig::default(), None);
+    session.start();
+    let right = Input {
+        direction: glm::Vec2::new(1., 0.),
+    };
+    for _ in 0..10 {
+        session.step(&right, TICK);
+    }
+    session.step(&Input::default(), TICK);
+    assert_eq!(
+        session.replay().inputs,
+        vec![
+            Span {
+                direction: [1., 0.],
+                ticks: 10
+            },
+            Span {
+                direction: [0., 0.],
+                ticks: 1
+            },
+        ]
+    );
+    assert_eq!(session.replay().ticks(), 11);
+}
+
+#[test]
+fn playback_is_bit_identical() {
+    let (session, events) = recorded_session();
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, GameEvent::Hit { .. })));
+    assert_eq!(session.replay().physics.len(), 1);
+
+    // Through a file and back, to make sure nothing is lost in the saving
+    let path: PathBuf = std::env::temp_dir()
+        .join(format!("r_circlegauntlet-replay-{}", std::process::id()))
+        .join("run.json");
+    session.replay().save(&path).unwrap();
+    let replay = Replay::load(&path).unwrap();
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    assert_eq!(&replay, session.replay());
+
+    let (game, replayed_events) = replay.run();
+    assert_eq!(replayed_events, events);
+    assert_eq!(fingerprint(&game), fingerprint(session.game()));
+}
+
+#[test]
+fn each_attempt_gets_its_own_recording() {
+    let mut session = Session::new(GameConfig::default(), None);
+    session.start();
+    for tick in 0..100 {
+        session.step(&wandering_input(tick), TICK);
+    }
+    session.handle(Action::Pause);
+    session.handle(Action::Retry);
+    assert_eq!(session.replay().ticks(), 0);
+    assert_eq!(session.replay().life, session.game().life());
+}
+
+#[test]
+fn mismatched_versions_are_refused() {
+    let (session, _) = recorded_session();
+    let mut replay = session.replay().clone();
+    replay.version = REPLAY_VERSION + 1;
+    let text = serde_json::to_string(&replay).unwrap();
+    let err = Replay::parse(&text).unwrap_err();
+    assert!(matches!(&err, FileError::Invalid { field, .. } if field == "version"));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "version: replay is format version {}, but this build can only play version {}",
+            REPLAY_VERSION + 1,
+            REPLAY_VERSION
+        )
+    );
+
+    let mut replay = session.replay().clone();
+    replay.tick_rate = TICK_RATE / 2;
+    let err = Replay::parse(&serde_json::to_string(&replay).unwrap()).unwrap_err();
+    assert!(matches!(&err, FileError::Invalid { field, .. } if field == "tick_rate"));
+
+    assert!(matches!(
+        Replay::parse("{\"version\": 1}"),
+        Err(FileError::Parse { .. })
+    ));
+}
+
+/// Parse `replay` after it's been saved, and what went wrong, if anything
+fn reparse(replay: &Replay) -> Result<Replay, String> {
+    Replay::parse(&serde_json::to_string(replay).unwrap()).map_err(|err| err.to_string())
+}
+
+#[test]
+fn replays_with_an_invalid_level_are_refused() {
+    let (session, _) = recorded_session();
+    let mut replay = session.replay().clone();
+    replay.level.player_radius = -0.1;
+    assert_eq!(
+        reparse(&replay).unwrap_err(),
+        "level.player_radius: must be positive"
+    );
+
+    let mut replay = session.replay().clone();
+    replay.level.obstacles[2].radius = 0.;
+    assert_eq!(
+        reparse(&replay).unwrap_err(),
+        "level.obstacle[2].radius: must be positive"
+    );
+}
+
+#[test]
+fn replays_with_invalid_physics_are_refused() {
+    let (session, _) = recorded_session();
+    let mut replay = session.replay().clone();
+    replay.config.physics.restitution = 2.;
+    assert!(reparse(&replay)
+        .unwrap_err()
+        .starts_with("config.physics.restitution: "));
+
+    // Changes partway through are checked too
+    let mut replay = session.replay().clone();
+    assert_eq!(replay.physics.len(), 1);
+    replay.physics[0].physics.drag = TICK_RATE as f32;
+    assert!(reparse(&replay)
+        .unwrap_err()
+        .starts_with("physics[0].physics.drag: "));
+
+    assert_eq!(reparse(session.replay()), Ok(session.replay().clone()));
+}
+
+#[test]
+fn campaign_levels_are_recorded_to_numbered_files() {
+    assert_eq!(
+        replay::numbered_path(Path::new("runs/best.json"), 2),
+        PathBuf::from("runs/best-2.json")
+    );
+    assert_eq!(
+        replay::numbered_path(Path::new("best"), 10),
+        PathBuf::from("best-10")
+    );
+}
@@ -0,0 +1,171 @@
+use r_circlegauntlet::score::{HighScores, Score, TABLE_SIZE};
+use r_circlegauntlet::*;
+use std::path::PathBuf;
+
+fn level() -> Level {
+    Level::parse(
+        r#"name = "Scored, with a comma"
+player_start = [-0.5, 0.0]
+
+[[goal]]
+pos = [0.5, 0.0]
+"#,
+    )
+    .unwrap()
+}
+
+fn score(points: u32) -> Score {
+    Score {
+        points,
+        ..Score::new(10., 5, 0, Difficulty::Normal)
+    }
+}
+
+#[test]
+fn points_for_time_life_near_misses_and_difficulty() {
+    // 6000 - 10 * 100 for time, 5 * 250 for life, 2 * 50 for near misses
+    assert_eq!(Score::new(10., 5, 2, Difficulty::Normal).points, 6350);
+    assert_eq!(Score::new(10., 5, 2, Difficulty::Hard).points, 9525);
+    assert_eq!(Score::new(10., 5, 2, Difficulty::Easy).points, 3175);
+    // Taking forever doesn't cost more than the time points
+    assert_eq!(Score::new(100., 1, 0, Difficulty::Nightmare).points, 500);
+}
+
+#[test]
+fn winning_scores_the_game() {
+    let mut session = Session::new(GameConfig::default(), Some(level()));
+    session.start();
+    let right = Input {
+        direction: glm::Vec2::new(1., 0.),
+    };
+    while !session.step(&right, TICK).contains(&GameEvent::Won) {}
+    let score = Score::of(session.game());
+    assert_eq!(score.seconds, session.game().time());
+    assert_eq!(score.life, LIFE_MAX);
+    assert_eq!(score.difficulty, Difficulty::Normal);
+    assert!(score.when > 0);
+}
+
+#[test]
+fn tables_keep_the_best_ten() {
+    let mut high_scores = HighScores::default();
+    assert_eq!(high_scores.table(&level()), &[]);
+    for points in 1..=TABLE_SIZE as u32 {
+        high_scores.add(&level(), score(points * 100));
+    }
+    assert_eq!(high_scores.add(&level(), score(50)), None);
+    assert_eq!(high_scores.add(&level(), score(550)), Some(5));
+    // Ties go to whoever got there first
+    assert_eq!(high_scores.add(&level(), score(1000)), Some(1));
+    let points: Vec<u32> = high_scores
+        .table(&level())
+        .iter()
+        .map(|score| score.points)
+        .collect();
+    assert_eq!(
+        points,
+        vec![1000, 1000, 900, 800, 700, 600, 550, 500, 400, 300]
+    );
+
+    // A different layout has its own table
+    let mut other = level();
+    other.player_start = [-0.5, 0.5];
+    assert_eq!(high_scores.table(&other), &[]);
+}
+
+#[test]
+fn high_scores_save_and_export() {
+    let mut high_scores = HighScores::default();
+    high_scores.add(&level(), score(1234));
+    let path: PathBuf = std::env::temp_dir()
+        .join(format!("r_circlegauntlet-scores-{}", std::process::id()))
+        .join("scores.toml");
+    high_scores.save(&path).unwrap();
+    assert_eq!(HighScores::load(&path).unwrap(), high_scores);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+    let mut csv = vec![];
+    high_scores.export_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "level,layout,rank,points,seconds,life,near_misses,difficulty,seed,when"
+    );
+    assert_eq!(
+        lines[1],
+        format!(
+            "\"Scored, with a comma\",{},1,1234,10.000,5,0,normal,0,0",
+            level().key()
+        )
+    );
+}
+
+#[test]
+fn seeds_too_big_for_toml_integers_are_saved() {
+    let mut high_scores = HighScores::default();
+    let big = Score {
+        seed: u64::MAX,
+        ..score(1234)
+    };
+    high_scores.add(&level(), big);
+    let path: PathBuf = std::env::temp_dir()
+        .join(format!("r_circlegauntlet-big-seed-{}", std::process::id()))
+        .join("scores.toml");
+    high_scores.save(&path).unwrap();
+    assert_eq!(HighScores::load(&path).unwrap(), high_scores);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+    // Tables saved before seeds were strings still load
+    let old: HighScores = toml::from_str(
+        r#"[levels.layout]
+name = "Old"
+
+[[levels.layout.scores]]
+points = 100
+seconds = 10.0
+life = 1
+near_misses = 0
+difficulty = "normal"
+seed = 42
+when = 0
+"#,
+    )
+    .unwrap();
+    assert_eq!(old.levels["layout"].scores[0].seed, 42);
+}
+
+/// Hold right past an obstacle whose edge is `gap` away from the player's path
+fn near_misses(gap: f32) -> u32 {
+    let y = PLAYER_RADIUS + OBSTACLE_RADIUS + gap;
+    let level = Level::parse(&format!(
+        r#"name = "Close shave"
+player_start = [-0.5, 0.0]
+
+[[goal]]
+pos = [0.8, 0.8]
+
+[[obstacle]]
+pos = [0.0, {}]
+"#,
+        y
+    ))
+    .unwrap();
+    let mut game = Game::with_level(GameConfig::default(), level);
+    let right = Input {
+        direction: glm::Vec2::new(1., 0.),
+    };
+    while game.player_pos()[0] < 0.4 {
+        game.step(&right, TICK);
+    }
+    game.near_misses()
+}
+
+#[test]
+fn close_shaves_count_as_near_misses() {
+    assert_eq!(near_misses(NEAR_MISS_MARGIN / 2.), 1);
+    // Touching isn't a miss
+    assert_eq!(near_misses(-0.02), 0);
+    // Nowhere near isn't either
+    assert_eq!(near_misses(NEAR_MISS_MARGIN * 2.), 0);
+}
@@ -0,0 +1,120 @@
+use r_circlegauntlet::*;
+
+fn config() -> GameConfig {
+    GameConfig {
+        seed: 7,
+        obstacle_count: 0,
+        ..GameConfig::default()
+    }
+}
+
+fn run_left_until_dead(session: &mut Session) {
+    let input = Input {
+        direction: glm::Vec2::new(-1.0, 0.0),
+    };
+    for _ in 0..TICK_RATE * 10 {
+        session.step(&input, TICK);
+        if session.state() != State::Playing {
+            break;
+        }
+    }
+}
+
+#[test]
+fn starts_at_the_title_and_does_nothing_until_started() {
+    let mut session = Session::new(config(), None);
+    assert_eq!(session.state(), State::Title);
+    let start = session.game().player_pos();
+    let input = Input {
+        direction: glm::Vec2::new(1.0, 0.0),
+    };
+    session.step(&input, TICK);
+    assert_eq!(session.game().player_pos(), start);
+
+    assert_eq!(session.handle(Action::Pause), State::Playing);
+    session.step(&input, TICK);
+    assert_ne!(session.game().player_pos(), start);
+}
+
+#[test]
+fn pause_freezes_the_game() {
+    let mut session = Session::new(config(), None);
+    session.start();
+    assert_eq!(session.handle(Action::Pause), State::Paused);
+    let pos = session.game().player_pos();
+    let input = Input {
+        direction: glm::Vec2::new(1.0, 0.0),
+    };
+    session.step(&input, TICK);
+    assert_eq!(session.game().player_pos(), pos);
+    assert_eq!(session.handle(Action::Pause), State::Playing);
+}
+
+#[test]
+fn retry_and_new_layout_only_mid_game_when_paused() {
+    let mut session = Session::new(config(), None);
+    session.start();
+    assert_eq!(session.handle(Action::Retry), State::Playing);
+    assert_eq!(session.handle(Action::NewLayout), State::Playing);
+    assert_eq!(session.game().seed(), 7);
+}
+
+#[test]
+fn dying_then_retrying_keeps_the_seed() {
+    let mut session = Session::new(config(), None);
+    session.start();
+    run_left_until_dead(&mut session);
+    assert_eq!(session.state(), State::Died);
+
+    // Nothing but a retry or new layout gets out of the died state
+    assert_eq!(session.handle(Action::Pause), State::Died);
+    assert_eq!(session.handle(Action::Retry), State::Playing);
+    assert_eq!(session.game().seed(), 7);
+    assert_eq!(session.game().life(), LIFE_MAX);
+    assert!(!session.game().is_over());
+}
+
+#[test]
+fn new_layout_changes_the_seed_reproducibly() {
+    let mut a = Session::new(config(), None);
+    let mut b = Session::new(config(), None);
+    a.start();
+    run_left_until_dead(&mut a);
+    assert_eq!(a.handle(Action::NewLayout), State::Playing);
+    assert_ne!(a.game().seed(), 7);
+
+    // A new layout from the title screen stays on the title screen
+    assert_eq!(b.handle(Action::NewLayout), State::Title);
+    assert_eq!(a.game().seed(), b.game().seed());
+}
+
+#[test]
+fn fixed_levels_keep_their_layout() {
+    let level = Level::load("levels/01_first_steps.toml").unwrap();
+    let mut session = Session::new(config(), Some(level.clone())).with_life(3);
+    session.handle(Action::Retry);
+    session.handle(Action::Pause);
+    assert_eq!(session.handle(Action::NewLayout), State::Playing);
+    assert_eq!(session.game().level(), &level);
+    assert_eq!(session.game().life(), 3);
+}
+
+#[test]
+fn winning_ends_in_the_won_state() {
+    let level = Level::parse(
+        r#"
+name = "Right there"
+player_start = [0.0, 0.0]
+
+[[goal]]
+pos = [0.05, 0.0]
+"#,
+    )
+    .unwrap();
+    let mut session = Session::new(config(), Some(level));
+    session.start();
+    let events = session.step(&Input::default(), TICK);
+    assert_eq!(events, vec![GameEvent::Won]);
+    assert_eq!(session.state(), State::Won);
+    assert_eq!(session.handle(Action::Retry), State::Playing);
+}
@@ -0,0 +1,154 @@
+use r_circlegauntlet::level::Boundary;
+use r_circlegauntlet::solver::{self, CLEARANCE_LIMIT};
+use r_circlegauntlet::*;
+
+/// A vertical wall of obstacles at x = 0 from top to bottom, leaving a gap of `gap` (surface to
+/// surface) around y = 0
+fn wall_level(gap: f32) -> Level {
+    let radius = 0.05;
+    let mut text = String::from(
+        r#"
+name = "Wall"
+player_start = [-0.75, 0.0]
+
+[[goal]]
+pos = [0.75, 0.0]
+"#,
+    );
+    let mut y = gap / 2. + radius;
+    while y <= 1. {
+        for &pos_y in &[y, -y] {
+            text.push_str(&format!(
+                "\n[[obstacle]]\npos = [0.0, {}]\nradius = {}\n",
+                pos_y, radius
+            ));
+        }
+        y += radius;
+    }
+    Level::parse(&text).unwrap()
+}
+
+#[test]
+fn an_empty_arena_is_wide_open() {
+    let analysis = solver::analyze(&wall_level(5.));
+    assert!(analysis.solvable);
+    assert_eq!(analysis.narrowest, Some(CLEARANCE_LIMIT * 2.));
+}
+
+#[test]
+fn narrowest_passage_is_the_gap_in_the_wall() {
+    let analysis = solver::analyze(&wall_level(0.2));
+    assert!(analysis.solvable);
+    let narrowest = analysis.narrowest.unwrap();
+    assert!(
+        (narrowest - 0.2).abs() < 0.02,
+        "narrowest passage was {}",
+        narrowest
+    );
+}
+
+#[test]
+fn a_gap_narrower_than_the_player_is_unsolvable() {
+    let analysis = solver::analyze(&wall_level(PLAYER_RADIUS * 1.5));
+    assert!(!analysis.solvable);
+    assert!(analysis.narrowest.unwrap() < PLAYER_RADIUS * 2.);
+}
+
+#[test]
+fn a_bigger_player_needs_a_wider_gap() {
+    let mut level = wall_level(0.2);
+    assert!(solver::analyze(&level).solvable);
+    level.player_radius = 0.12;
+    assert!(!solver::analyze(&level).solvable);
+}
+
+#[test]
+fn a_goal_outside_the_arena_is_unreachable() {
+    let mut level = wall_level(0.5);
+    level.goals[0].pos = [3.0, 3.0];
+    let analysis = solver::analyze(&level);
+    assert!(!analysis.solvable);
+    assert_eq!(analysis.narrowest, None);
+}
+
+#[test]
+fn generated_layouts_are_solvable() {
+    for seed in 0..30 {
+        let config = GameConfig {
+            seed,
+            ..GameConfig::default()
+        };
+        assert!(
+            solver::analyze(&Level::generate(&config)).solvable,
+            "seed {} generated an unsolvable layout",
+            seed
+        );
+    }
+}
+
+#[test]
+fn crowded_generated_layouts_are_solvable() {
+    for (seed, obstacle_count) in [(1, 200), (2, 1000), (3, 4000)] {
+        let config = GameConfig {
+            seed,
+            obstacle_count,
+            obstacle_spacing: 0.0005,
+            enemy_spacing: 0.0005,
+            ..GameConfig::default()
+        };
+        let level = Level::generate(&config);
+        assert!(
+            solver::analyze(&level).solvable,
+            "{} obstacles generated an unsolvable layout",
+            obstacle_count
+        );
+        // Only as many obstacles as it took are cleared out of the way
+        assert!(level.obstacles.len() > obstacle_count / 2);
+    }
+}
+
+#[test]
+fn bundled_levels_are_solvable() {
+    let campaign = Campaign::load("levels/campaign.toml").unwrap();
+    for path in &campaign.levels {
+        let level = Level::load(path).unwrap();
+        assert!(
+            solver::analyze(&level).solvable,
+            "{} is unsolvable",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn the_arena_edge_narrows_gaps_unless_it_wraps() {
+    // Obstacles from the bottom of the arena up to a gap against the top edge that's too narrow
+    let radius = 0.05;
+    let mut text = String::from(
+        r#"
+name = "Squeeze"
+player_start = [-0.75, 0.0]
+
+[[goal]]
+pos = [0.75, 0.0]
+"#,
+    );
+    let mut y = 1. - PLAYER_RADIUS * 1.5 - radius;
+    while y >= -1. {
+        text.push_str(&format!(
+            "\n[[obstacle]]\npos = [0.0, {}]\nradius = {}\n",
+            y, radius
+        ));
+        y -= radius;
+    }
+    let mut level = Level::parse(&text).unwrap();
+    for &boundary in &[Boundary::Deadly, Boundary::Bouncy, Boundary::Painful] {
+        level.boundary = boundary;
+        let analysis = solver::analyze(&level);
+        assert!(!analysis.solvable, "{:?} was solvable", boundary);
+        assert!(analysis.narrowest.unwrap() < PLAYER_RADIUS * 2.);
+    }
+    // Wrapping around the top edge goes past the obstacles altogether
+    level.boundary = Boundary::Wrap;
+    assert!(solver::analyze(&level).solvable);
+}
@@ -0,0 +1,52 @@
+use r_circlegauntlet::glm::Vec2;
+use r_circlegauntlet::spatial::SpatialHash;
+use r_circlegauntlet::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+#[test]
+fn query_finds_everything_brute_force_does() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let circles: Vec<(Vec2, f32)> = (0..500)
+        .map(|_| {
+            let pos = Vec2::new(rng.gen_range(-1.2..1.2), rng.gen_range(-1.2..1.2));
+            (pos, rng.gen_range(0.0..0.2))
+        })
+        .collect();
+    let grid = SpatialHash::from_circles(0.1, &circles);
+    assert_eq!(grid.len(), circles.len());
+    for _ in 0..200 {
+        let pos = Vec2::new(rng.gen_range(-1.2..1.2), rng.gen_range(-1.2..1.2));
+        let radius = rng.gen_range(0.0..0.3);
+        let found = grid.query(pos, radius);
+        for (i, &(other, other_radius)) in circles.iter().enumerate() {
+            if (pos - other).magnitude() < radius + other_radius {
+                assert!(found.contains(&i), "missed circle {} near {:?}", i, pos);
+            }
+        }
+        // Listed once each, in order
+        assert!(found.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
+
+#[test]
+fn crowded_levels_generate_quickly() {
+    // Thousands of obstacles is only practical with the grid doing the spacing checks
+    let config = GameConfig {
+        obstacle_count: 2000,
+        obstacle_spacing: 0.0005,
+        enemy_spacing: 0.0005,
+        ..GameConfig::default()
+    };
+    let level = Level::generate(&config);
+    // There's no way through that many, so the ones in the way are cleared, but only those
+    assert!(
+        (1000..2000).contains(&level.obstacles.len()),
+        "{} obstacles",
+        level.obstacles.len()
+    );
+    let mut game = Game::with_level(config, level);
+    for _ in 0..TICK_RATE {
+        game.step(&Input::default(), TICK);
+    }
+}
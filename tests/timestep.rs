@@ -0,0 +1,47 @@
+use r_circlegauntlet::*;
+
+#[test]
+fn ticks_do_not_depend_on_frame_rate() {
+    let mut fast = FixedTimestep::default();
+    let mut slow = FixedTimestep::default();
+    let fast_ticks: u32 = (0..60).map(|_| fast.advance(1. / 60.)).sum();
+    let slow_ticks: u32 = (0..15).map(|_| slow.advance(1. / 15.)).sum();
+    assert_eq!(fast_ticks, slow_ticks);
+    assert!((fast_ticks as i64 - TICK_RATE as i64).abs() <= 1);
+}
+
+#[test]
+fn long_hitches_are_capped() {
+    let mut timestep = FixedTimestep::default();
+    let ticks = timestep.advance(10.0);
+    assert!(ticks <= TICK_RATE / 4 + 1);
+    assert!(timestep.alpha() < 1.0);
+}
+
+#[test]
+fn same_inputs_same_trajectory() {
+    let config = GameConfig {
+        obstacle_count: 0,
+        ..GameConfig::default()
+    };
+    let mut a = Game::new(config.clone());
+    let mut b = Game::new(config);
+    let input = Input {
+        direction: glm::Vec2::new(1.0, -1.0).normalize(),
+    };
+    let mut ta = FixedTimestep::default();
+    let mut tb = FixedTimestep::default();
+    // Same real time, chopped into very different frame lengths
+    for _ in 0..120 {
+        for _ in 0..ta.advance(1. / 120.) {
+            a.step(&input, ta.tick());
+        }
+    }
+    for _ in 0..10 {
+        for _ in 0..tb.advance(1. / 10.) {
+            b.step(&input, tb.tick());
+        }
+    }
+    assert_eq!(a.player_pos(), b.player_pos());
+    assert_eq!(a.life(), b.life());
+}
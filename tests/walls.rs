@@ -0,0 +1,105 @@
+use r_circlegauntlet::level::WallSpec;
+use r_circlegauntlet::solver;
+use r_circlegauntlet::*;
+
+/// A vertical wall segment at x = 0 from the top of the arena down to `bottom`, with the player on
+/// the left and the goal on the right
+fn fence(bottom: f32) -> Level {
+    Level::parse(&format!(
+        r#"name = "Fence"
+player_start = [-0.5, 0.5]
+
+[[goal]]
+pos = [0.5, 0.5]
+
+[[wall]]
+type = "segment"
+from = [0.0, 1.0]
+to = [0.0, {}]
+"#,
+        bottom
+    ))
+    .unwrap()
+}
+
+#[test]
+fn segments_become_thin_rectangles() {
+    let level = fence(-0.5);
+    assert_eq!(
+        level.walls[0],
+        WallSpec::Segment {
+            from: [0., 1.],
+            to: [0., -0.5],
+            thickness: WALL_THICKNESS,
+        }
+    );
+    let (center, half_size, angle) = level.walls[0].rectangle();
+    assert!((center - glm::Vec2::new(0., 0.25)).magnitude() < 1e-6);
+    assert!((half_size - glm::Vec2::new(0.75, WALL_THICKNESS / 2.)).magnitude() < 1e-6);
+    assert!((angle + std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+}
+
+#[test]
+fn wall_values_are_checked() {
+    let err = Level::parse(
+        r#"name = "Flat"
+player_start = [-0.5, 0.5]
+
+[[goal]]
+pos = [0.5, -0.5]
+
+[[wall]]
+type = "rectangle"
+center = [0.0, 0.0]
+size = [0.5, 0.0]
+"#,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "wall[0].size: must be positive");
+
+    let err = Level::parse(
+        r#"name = "Dot"
+player_start = [-0.5, 0.5]
+
+[[goal]]
+pos = [0.5, -0.5]
+
+[[wall]]
+type = "segment"
+from = [0.2, 0.2]
+to = [0.2, 0.2]
+"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "wall[0].to: must be somewhere other than `from`"
+    );
+}
+
+#[test]
+fn walls_block_and_hurt_the_player() {
+    let mut game = Game::with_level(GameConfig::default(), fence(-1.0));
+    let input = Input {
+        direction: glm::Vec2::new(1., 0.),
+    };
+    let mut hits = 0;
+    for _ in 0..TICK_RATE * 3 {
+        for event in game.step(&input, TICK) {
+            if let GameEvent::Hit { .. } = event {
+                hits += 1;
+            }
+        }
+        assert!(game.player_pos()[0] < 0., "went through the wall");
+    }
+    assert!(hits >= 1);
+}
+
+#[test]
+fn the_solver_sees_walls() {
+    // Floor to ceiling: no way around
+    let analysis = solver::analyze(&fence(-1.0));
+    assert!(!analysis.solvable);
+    // A gap at the bottom as wide as the arena's lower half
+    assert!(solver::analyze(&fence(0.0)).solvable);
+}